@@ -0,0 +1,324 @@
+//! The `bench` subcommand, which runs the solution for one part of one day several times and
+//! reports statistics about how long loading the input and solving the problem took.
+
+use clap::{self, ArgMatches};
+
+use base::{Error, Part};
+use base::registry::Registry;
+
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use {exit_code, json, print_error, table};
+use input::Input;
+use run::format_duration;
+
+pub fn bench(registry: &Registry, matches: &ArgMatches, inputs_dir: &Path) -> i32 {
+    let day = value_t!(matches, "day", u32).unwrap_or_else(|e| e.exit());
+    let part = value_t!(matches, "part", Part).unwrap_or_else(|e| e.exit());
+    let iterations = value_t!(matches, "iterations", usize).unwrap_or_else(|e| e.exit());
+    let warmup = value_t!(matches, "warmup", usize).unwrap_or_else(|e| e.exit());
+    if iterations == 0 {
+        clap::Error::value_validation_auto("iterations must be at least 1".to_owned()).exit();
+    }
+    let input = Input::from_matches(matches, day, inputs_dir);
+
+    let report = match measure(registry, day, part, input, warmup, iterations) {
+        Ok(report) => report,
+        Err(error) => {
+            print_error(&error);
+            return exit_code(&error);
+        }
+    };
+
+    let output = match matches.value_of("export") {
+        Some("csv") => report.to_csv(),
+        Some("json") => report.to_json(),
+        _ => report.to_table(),
+    };
+    match matches.value_of("output") {
+        Some(output_path) => {
+            if let Err(error) = fs::write(output_path, output + "\n") {
+                let error = Error::internal(format!("could not write results to {}", output_path))
+                    .with_source(error);
+                print_error(&error);
+                return exit_code(&error);
+            }
+        }
+        None => println!("{}", output),
+    }
+    0
+}
+
+/// Runs the solver `warmup` times without measuring, and then `iterations` times while measuring
+/// loading of the input, parsing and solving separately. Parsing is only measured for solvers that
+/// parse the input separately from solving. Input from stdin can only be read once, so it is
+/// read before measuring anything.
+fn measure(registry: &Registry,
+           day: u32,
+           part: Part,
+           input: Input,
+           warmup: usize,
+           iterations: usize)
+           -> Result<Report, Error> {
+    let solver = registry.solver(day, part)?;
+
+    let input = match input {
+        Input::Stdin => Input::Literal(input.read()?),
+        input => input,
+    };
+
+    for _ in 0..warmup {
+        let input = input.read()?;
+        solver.solve(&input, part)?;
+    }
+
+    let mut load_times = Vec::with_capacity(iterations);
+    let mut parse_times = Vec::with_capacity(iterations);
+    let mut solve_times = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let timer = Instant::now();
+        let input = input.read()?;
+        load_times.push(timer.elapsed());
+
+        let mut solved = solver.solve_parts(&input, &[part])?;
+        let solved_part = solved.parts.remove(0);
+        solved_part.result?;
+        parse_times.extend(solved.parse_time);
+        solve_times.push(solved_part.elapsed);
+    }
+
+    Ok(Report {
+        day,
+        part,
+        warmup,
+        iterations,
+        load: Statistics::from_durations(&load_times),
+        parse: if parse_times.is_empty() {
+            None
+        } else {
+            Some(Statistics::from_durations(&parse_times))
+        },
+        solve: Statistics::from_durations(&solve_times),
+    })
+}
+
+const CSV_HEADER: &str = "day,part,phase,iterations,min_ns,median_ns,mean_ns,p95_ns,std_dev_ns";
+
+/// The results of benchmarking one part of one day.
+struct Report {
+    day: u32,
+    part: Part,
+    warmup: usize,
+    iterations: usize,
+    load: Statistics,
+    /// Only present if the solver parses the input separately from solving.
+    parse: Option<Statistics>,
+    solve: Statistics,
+}
+
+impl Report {
+    fn phases(&self) -> Vec<(&'static str, &Statistics)> {
+        let mut phases = vec![("load", &self.load)];
+        if let Some(ref parse) = self.parse {
+            phases.push(("parse", parse));
+        }
+        phases.push(("solve", &self.solve));
+        phases
+    }
+
+    fn to_table(&self) -> String {
+        let header = ["Phase", "Min", "Median", "Mean", "p95", "Std dev"];
+        let rows = self.phases()
+            .iter()
+            .map(|&(phase, stats)| {
+                let mut row = vec![phase.to_owned()];
+                row.extend(stats.values().iter().map(|&d| format!("{}s", format_duration(d))));
+                row
+            })
+            .collect::<Vec<_>>();
+        format!("Day {} part {}: {} iterations after {} warm-up runs\n\n{}",
+                self.day,
+                self.part,
+                self.iterations,
+                self.warmup,
+                table::format_table(&header, &rows))
+    }
+
+    fn to_csv(&self) -> String {
+        let mut lines = vec![CSV_HEADER.to_owned()];
+        for &(phase, stats) in &self.phases() {
+            let values = stats.values()
+                .iter()
+                .map(|&d| nanos(d).to_string())
+                .collect::<Vec<String>>();
+            lines.push(format!("{},{},{},{},{}",
+                               self.day,
+                               self.part,
+                               phase,
+                               self.iterations,
+                               values.join(",")));
+        }
+        lines.join("\n")
+    }
+
+    fn to_json(&self) -> String {
+        let phases = self.phases()
+            .iter()
+            .map(|&(phase, stats)| {
+                format!("{}: {{\"min_ns\": {}, \"median_ns\": {}, \"mean_ns\": {}, \"p95_ns\": {}, \
+                         \"std_dev_ns\": {}}}",
+                        json::string(phase),
+                        nanos(stats.min),
+                        nanos(stats.median),
+                        nanos(stats.mean),
+                        nanos(stats.p95),
+                        nanos(stats.std_dev))
+            })
+            .collect::<Vec<String>>();
+        format!("{{\"day\": {}, \"part\": {}, \"warmup\": {}, \"iterations\": {}, {}}}",
+                self.day,
+                self.part,
+                self.warmup,
+                self.iterations,
+                phases.join(", "))
+    }
+}
+
+fn nanos(duration: Duration) -> u128 {
+    duration.as_nanos()
+}
+
+/// Summary statistics of a set of measured durations.
+#[derive(Debug, PartialEq)]
+struct Statistics {
+    min: Duration,
+    median: Duration,
+    mean: Duration,
+    /// The 95th percentile, using the nearest-rank method.
+    p95: Duration,
+    /// The population standard deviation.
+    std_dev: Duration,
+}
+
+impl Statistics {
+    /// Calculates the statistics of `durations`, which must not be empty.
+    fn from_durations(durations: &[Duration]) -> Statistics {
+        assert!(!durations.is_empty(), "cannot calculate statistics without any durations");
+
+        let mut sorted = durations.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+
+        let p95_rank = (n * 95).div_ceil(100); // ceil(0.95 * n), without floating point errors
+        let p95 = sorted[p95_rank.max(1) - 1];
+
+        let nanos = sorted.iter().map(|d| d.as_nanos() as f64).collect::<Vec<f64>>();
+        let mean = nanos.iter().sum::<f64>() / n as f64;
+        let variance = nanos.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n as f64;
+
+        Statistics {
+            min: sorted[0],
+            median,
+            mean: Duration::from_nanos(mean.round() as u64),
+            p95,
+            std_dev: Duration::from_nanos(variance.sqrt().round() as u64),
+        }
+    }
+
+    /// The statistics in the order they are presented: min, median, mean, p95 and std dev.
+    fn values(&self) -> [Duration; 5] {
+        [self.min, self.median, self.mean, self.p95, self.std_dev]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    #[test]
+    fn test_statistics_single() {
+        let stats = Statistics::from_durations(&millis(&[5]));
+        assert_eq!(Duration::from_millis(5), stats.min);
+        assert_eq!(Duration::from_millis(5), stats.median);
+        assert_eq!(Duration::from_millis(5), stats.mean);
+        assert_eq!(Duration::from_millis(5), stats.p95);
+        assert_eq!(Duration::from_millis(0), stats.std_dev);
+    }
+
+    #[test]
+    fn test_statistics_odd() {
+        let stats = Statistics::from_durations(&millis(&[5, 4, 2, 7, 4, 5, 4]));
+        assert_eq!(Duration::from_millis(2), stats.min);
+        assert_eq!(Duration::from_millis(4), stats.median);
+        assert_eq!(Duration::from_millis(7), stats.p95);
+    }
+
+    #[test]
+    fn test_statistics_even() {
+        let stats = Statistics::from_durations(&millis(&[2, 4, 4, 4, 5, 5, 7, 9]));
+        assert_eq!(Duration::from_millis(2), stats.min);
+        assert_eq!(Duration::new(0, 4_500_000), stats.median);
+        assert_eq!(Duration::from_millis(5), stats.mean);
+        assert_eq!(Duration::from_millis(9), stats.p95);
+        assert_eq!(Duration::from_millis(2), stats.std_dev);
+    }
+
+    #[test]
+    fn test_statistics_p95() {
+        let durations = (1..101).map(Duration::from_millis).collect::<Vec<Duration>>();
+        let stats = Statistics::from_durations(&durations);
+        assert_eq!(Duration::from_millis(95), stats.p95);
+    }
+
+    fn report() -> Report {
+        Report {
+            day: 1,
+            part: Part::Two,
+            warmup: 3,
+            iterations: 2,
+            load: Statistics::from_durations(&millis(&[1, 3])),
+            parse: Some(Statistics::from_durations(&millis(&[2, 2]))),
+            solve: Statistics::from_durations(&millis(&[10, 10])),
+        }
+    }
+
+    #[test]
+    fn test_phases_without_parse() {
+        let report = Report { parse: None, ..report() };
+        let phases = report.phases().iter().map(|&(phase, _)| phase).collect::<Vec<&str>>();
+        assert_eq!(vec!["load", "solve"], phases);
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let expected = "day,part,phase,iterations,min_ns,median_ns,mean_ns,p95_ns,std_dev_ns\n\
+                        1,2,load,2,1000000,2000000,2000000,3000000,1000000\n\
+                        1,2,parse,2,2000000,2000000,2000000,2000000,0\n\
+                        1,2,solve,2,10000000,10000000,10000000,10000000,0";
+        assert_eq!(expected, report().to_csv());
+    }
+
+    #[test]
+    fn test_to_json() {
+        let expected = "{\"day\": 1, \"part\": 2, \"warmup\": 3, \"iterations\": 2, \
+                        \"load\": {\"min_ns\": 1000000, \"median_ns\": 2000000, \
+                        \"mean_ns\": 2000000, \"p95_ns\": 3000000, \"std_dev_ns\": 1000000}, \
+                        \"parse\": {\"min_ns\": 2000000, \"median_ns\": 2000000, \
+                        \"mean_ns\": 2000000, \"p95_ns\": 2000000, \"std_dev_ns\": 0}, \
+                        \"solve\": {\"min_ns\": 10000000, \"median_ns\": 10000000, \
+                        \"mean_ns\": 10000000, \"p95_ns\": 10000000, \"std_dev_ns\": 0}}";
+        assert_eq!(expected, report().to_json());
+    }
+}
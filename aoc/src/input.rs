@@ -0,0 +1,109 @@
+//! Selection and reading of the input to a problem, which can come from a file, from stdin, or be
+//! given directly on the command line.
+
+use clap::ArgMatches;
+
+use base::Error;
+use base::utils;
+
+use std::fmt;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// The file name that means "read from stdin" when given as `--file`.
+const STDIN: &str = "-";
+
+#[derive(Debug, PartialEq)]
+pub enum Input {
+    Literal(String),
+    File(PathBuf),
+    Stdin,
+}
+
+impl Input {
+    /// Selects the input from the `file` and `input` arguments. If neither is given, the default
+    /// input for `day` in `inputs_dir` is used.
+    pub fn from_matches(matches: &ArgMatches, day: u32, inputs_dir: &Path) -> Input {
+        if let Some(literal) = matches.value_of("input") {
+            Input::Literal(literal.to_owned())
+        } else {
+            match matches.value_of("file") {
+                Some(STDIN) => Input::Stdin,
+                Some(file) => Input::File(PathBuf::from(file)),
+                None => Input::default_for(day, inputs_dir),
+            }
+        }
+    }
+
+    /// The input for `day` in `inputs_dir`, i.e. `inputs_dir/dayNN.input`.
+    pub fn default_for(day: u32, inputs_dir: &Path) -> Input {
+        Input::File(inputs_dir.join(format!("day{:02}.input", day)))
+    }
+
+    pub fn read(&self) -> Result<String, Error> {
+        match *self {
+            Input::Literal(ref literal) => Ok(literal.clone()),
+            Input::File(ref path) => utils::read_file_as_string(path),
+            Input::Stdin => {
+                let mut buf = String::new();
+                io::stdin().read_to_string(&mut buf).map_err(|e| Error::io("<stdin>", e))?;
+                Ok(buf)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Input::Literal(_) => write!(f, "<literal>"),
+            Input::File(ref path) => write!(f, "{}", path.display()),
+            Input::Stdin => write!(f, "<stdin>"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use clap::{App, Arg};
+
+    fn matches(args: &[&str]) -> ArgMatches<'static> {
+        App::new("test")
+            .arg(Arg::with_name("file").long("file").takes_value(true))
+            .arg(Arg::with_name("input").long("input").takes_value(true))
+            .get_matches_from(Some("test").iter().chain(args.iter()))
+    }
+
+    #[test]
+    fn test_from_matches_default() {
+        let input = Input::from_matches(&matches(&[]), 3, Path::new("dir"));
+        assert_eq!(Input::File(PathBuf::from("dir/day03.input")), input);
+    }
+
+    #[test]
+    fn test_from_matches_file() {
+        let input = Input::from_matches(&matches(&["--file", "x.txt"]), 3, Path::new("dir"));
+        assert_eq!(Input::File(PathBuf::from("x.txt")), input);
+    }
+
+    #[test]
+    fn test_from_matches_stdin() {
+        let input = Input::from_matches(&matches(&["--file", "-"]), 3, Path::new("dir"));
+        assert_eq!(Input::Stdin, input);
+    }
+
+    #[test]
+    fn test_from_matches_literal() {
+        let input = Input::from_matches(&matches(&["--input", "R2, L3"]), 3, Path::new("dir"));
+        assert_eq!(Input::Literal("R2, L3".to_owned()), input);
+        assert_eq!("R2, L3", input.read().unwrap());
+    }
+
+    #[test]
+    fn test_read_missing_file() {
+        let input = Input::File(PathBuf::from("this/file/does/not/exist"));
+        assert!(matches!(input.read(), Err(Error::Io { .. })));
+    }
+}
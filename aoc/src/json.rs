@@ -0,0 +1,51 @@
+//! Minimal helpers for writing JSON by hand, for the few places where the `aoc` utility produces
+//! machine-readable output.
+
+use std::fmt::Write;
+
+/// Formats a string as a JSON string literal, escaping characters as needed. All control
+/// characters are escaped, so the result is also a valid TOML basic string.
+pub fn string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Formats an optional string as a JSON string literal, or `null` if there is no string.
+pub fn optional_string(s: Option<&str>) -> String {
+    s.map_or("null".to_owned(), string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string() {
+        assert_eq!("\"abc\"", string("abc"));
+        assert_eq!("\"a\\\"b\\\\c\"", string("a\"b\\c"));
+        assert_eq!("\"#.\\n.#\"", string("#.\n.#"));
+        assert_eq!("\"\\u0001\"", string("\u{1}"));
+        assert_eq!("\"\\u007f\\u009b\"", string("\u{7f}\u{9b}"));
+    }
+
+    #[test]
+    fn test_optional_string() {
+        assert_eq!("\"abc\"", optional_string(Some("abc")));
+        assert_eq!("null", optional_string(None));
+    }
+}
@@ -2,15 +2,19 @@
 extern crate clap;
 use clap::App;
 
+extern crate toml;
+
 extern crate base;
-use base::ProblemSolver;
+use base::Error;
+use base::registry::Registry;
 
 extern crate day01;
 extern crate day02;
 extern crate day03;
 extern crate day04;
 
-use std::time::Instant;
+use std::path::Path;
+use std::process;
 
 macro_rules! err_println {
     ( $( $arg : tt )* ) => {{
@@ -19,51 +23,64 @@ macro_rules! err_println {
     }}
 }
 
-enum Input {
-    Literal(String),
-    File(String),
-}
+mod bench;
+mod input;
+mod json;
+mod output;
+mod run;
+mod table;
+mod verify;
 
 fn main() {
-    let yaml = load_yaml!("cli.yml");
-    let matches = App::from_yaml(yaml).get_matches();
+    let registry = registry();
+    let days_help = format!("Available solutions:\n{}", registry.summary());
 
-    let day = value_t!(matches, "day", u32).unwrap();
-    let part = value_t!(matches, "part", base::Part).unwrap();
-    let input = if matches.is_present("input") {
-        Input::Literal(matches.value_of("input").unwrap().to_string())
-    } else {
-        Input::File(matches.value_of("file").unwrap().to_string())
-    };
+    let yaml = load_yaml!("cli.yml");
+    let matches = App::from_yaml(yaml).after_help(days_help.as_str()).get_matches();
 
-    let timer = Instant::now();
+    let (subcommand, subcommand_matches) = matches.subcommand();
+    let inputs_dir = subcommand_matches.and_then(|m| m.value_of("inputs-dir"))
+        .or_else(|| matches.value_of("inputs-dir"))
+        .map(Path::new)
+        .unwrap();
 
-    let solver = get_solver(day).unwrap();
-    let solution = match input {
-        Input::Literal(literal) => solver.solve(&literal, part),
-        Input::File(file_path) => {
-            let input = base::utils::read_file_as_string(&file_path);
-            solver.solve(&input, part)
-        }
+    let exit_code = match (subcommand, subcommand_matches) {
+        ("run", Some(run_matches)) => run::run(&registry, run_matches, inputs_dir),
+        ("verify", Some(verify_matches)) => verify::verify(&registry, verify_matches, inputs_dir),
+        ("bench", Some(bench_matches)) => bench::bench(&registry, bench_matches, inputs_dir),
+        _ => unreachable!("clap requires a subcommand"),
     };
 
-    match solution {
-        Ok(answer) => println!("{}", answer),
-        Err(error) => err_println!("Error: {}", error),
-    };
+    process::exit(exit_code);
+}
 
-    let elapsed = timer.elapsed();
-    println!("Time elapsed: {}.{:09} seconds",
-             elapsed.as_secs(),
-             elapsed.subsec_nanos());
+/// Creates a registry containing all the solutions. This is the only place that needs to be
+/// updated (apart from `Cargo.toml`) when a solution for a new day is added.
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.register(day01::DESCRIPTOR);
+    registry.register(day02::DESCRIPTOR);
+    registry.register(day03::DESCRIPTOR);
+    registry.register(day04::DESCRIPTOR);
+    registry
+}
+
+/// Prints the error and the chain of errors that caused it to stderr.
+fn print_error(error: &Error) {
+    err_println!("Error: {}", error);
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        err_println!("  caused by: {}", cause);
+        source = cause.source();
+    }
 }
 
-fn get_solver(day: u32) -> Result<Box<ProblemSolver>, String> {
-    match day {
-        1 => Ok(day01::get_solver()),
-        2 => Ok(day02::get_solver()),
-        3 => Ok(day03::get_solver()),
-        4 => Ok(day04::get_solver()),
-        _ => Err("day either invalid or not implemented yet".to_string()),
+/// Maps the kind of error to an exit code, following the conventions in `sysexits.h`.
+fn exit_code(error: &Error) -> i32 {
+    match *error {
+        Error::Parse { .. } | Error::InvalidInput(_) => 65, // EX_DATAERR
+        Error::Io { .. } => 66, // EX_NOINPUT
+        Error::NotImplemented(_) => 69, // EX_UNAVAILABLE
+        Error::Internal { .. } => 70, // EX_SOFTWARE
     }
 }
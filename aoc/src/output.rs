@@ -0,0 +1,155 @@
+//! Machine-readable output of the results of the `run` subcommand, with one record per part that
+//! was run. Every record has the same fields regardless of whether the part succeeded or not:
+//!
+//! * `day` and `part`.
+//! * `status`, which is one of `ok`, `error` or `not_implemented`.
+//! * `answer`, if the part was solved.
+//! * `error`, if the part failed or has not been implemented.
+//! * `parse_ns` and `solve_ns`, the time spent parsing the input and solving the part, in
+//!   nanoseconds. Either can be missing, e.g. when the input could not be read.
+//! * `input`, which describes where the input was read from.
+
+use base::Error;
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use json;
+use run::Row;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// Human-readable text, which is not meant to be parsed.
+    Text,
+    /// One JSON object per line.
+    Json,
+    /// Tab-separated values, with a header line.
+    Tsv,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "tsv" => Ok(Format::Tsv),
+            _ => Err(Error::parse(format!("invalid format: {}", s))),
+        }
+    }
+}
+
+const TSV_HEADER: &str = "day\tpart\tstatus\tanswer\terror\tparse_ns\tsolve_ns\tinput";
+
+/// Formats the rows as records in the given format, which must not be `Format::Text`.
+pub fn records(rows: &[Row], format: Format) -> String {
+    match format {
+        Format::Json => rows.iter().map(to_json).collect::<Vec<String>>().join("\n"),
+        Format::Tsv => {
+            let mut lines = vec![TSV_HEADER.to_owned()];
+            lines.extend(rows.iter().map(to_tsv));
+            lines.join("\n")
+        }
+        Format::Text => panic!("text output is not made of records"),
+    }
+}
+
+fn status(row: &Row) -> &'static str {
+    match row.result {
+        Ok(_) => "ok",
+        Err(Error::NotImplemented(_)) => "not_implemented",
+        Err(_) => "error",
+    }
+}
+
+fn nanos(duration: Option<Duration>) -> Option<String> {
+    duration.map(|d| d.as_nanos().to_string())
+}
+
+fn to_json(row: &Row) -> String {
+    let answer = row.result.as_ref().ok().map(ToString::to_string);
+    let error = row.result.as_ref().err().map(ToString::to_string);
+    let null = || "null".to_owned();
+    format!("{{\"day\": {}, \"part\": {}, \"status\": {}, \"answer\": {}, \"error\": {}, \
+             \"parse_ns\": {}, \"solve_ns\": {}, \"input\": {}}}",
+            row.day,
+            row.part,
+            json::string(status(row)),
+            json::optional_string(answer.as_deref()),
+            json::optional_string(error.as_deref()),
+            nanos(row.parse_time).unwrap_or_else(&null),
+            nanos(row.elapsed).unwrap_or_else(&null),
+            json::string(&row.input))
+}
+
+/// Formats a row as tab-separated values. Missing values are left empty, and tabs and newlines
+/// within values are escaped as `\t` and `\n`.
+fn to_tsv(row: &Row) -> String {
+    let escape = |s: String| s.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n");
+    let answer = row.result.as_ref().ok().map_or(String::new(), |a| escape(a.to_string()));
+    let error = row.result.as_ref().err().map_or(String::new(), |e| escape(e.to_string()));
+    [row.day.to_string(),
+     row.part.to_string(),
+     status(row).to_owned(),
+     answer,
+     error,
+     nanos(row.parse_time).unwrap_or_default(),
+     nanos(row.elapsed).unwrap_or_default(),
+     escape(row.input.clone())]
+        .join("\t")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use base::{Answer, Part};
+
+    fn rows() -> Vec<Row> {
+        vec![Row {
+                 day: 2,
+                 part: Part::One,
+                 result: Ok(Answer::pixels_from_str("#.\n.#").unwrap()),
+                 parse_time: Some(Duration::from_nanos(10)),
+                 elapsed: Some(Duration::from_nanos(250)),
+                 input: "inputs/day02.input".to_owned(),
+             },
+             Row {
+                 day: 2,
+                 part: Part::Two,
+                 result: Err(Error::not_implemented("day 2 part 2")),
+                 parse_time: None,
+                 elapsed: None,
+                 input: "inputs/day02.input".to_owned(),
+             }]
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!(Format::Json, Format::from_str("json").unwrap());
+        assert_eq!(Format::Tsv, Format::from_str("tsv").unwrap());
+        assert!(Format::from_str("xml").is_err());
+    }
+
+    #[test]
+    fn test_records_json() {
+        let expected = "{\"day\": 2, \"part\": 1, \"status\": \"ok\", \"answer\": \"#.\\n.#\", \
+                        \"error\": null, \"parse_ns\": 10, \"solve_ns\": 250, \
+                        \"input\": \"inputs/day02.input\"}\n\
+                        {\"day\": 2, \"part\": 2, \"status\": \"not_implemented\", \
+                        \"answer\": null, \"error\": \"not implemented: day 2 part 2\", \
+                        \"parse_ns\": null, \"solve_ns\": null, \
+                        \"input\": \"inputs/day02.input\"}";
+        assert_eq!(expected, records(&rows(), Format::Json));
+    }
+
+    #[test]
+    fn test_records_tsv() {
+        let expected = "day\tpart\tstatus\tanswer\terror\tparse_ns\tsolve_ns\tinput\n\
+                        2\t1\tok\t#.\\n.#\t\t10\t250\tinputs/day02.input\n\
+                        2\t2\tnot_implemented\t\tnot implemented: day 2 part 2\t\t\t\
+                        inputs/day02.input";
+        assert_eq!(expected, records(&rows(), Format::Tsv));
+    }
+}
@@ -0,0 +1,315 @@
+//! The `run` subcommand, which runs either a single part of a single day, or every registered
+//! solution.
+
+use clap::ArgMatches;
+
+use base::{Answer, Error, Part};
+use base::registry::{Descriptor, Registry};
+
+use std::error::Error as StdError;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use {exit_code, print_error, table};
+use input::Input;
+use output::{self, Format};
+
+const PARTS: [Part; 2] = [Part::One, Part::Two];
+
+pub fn run(registry: &Registry, matches: &ArgMatches, inputs_dir: &Path) -> i32 {
+    let format = value_t!(matches, "format", Format).unwrap_or_else(|e| e.exit());
+    let rows = if matches.is_present("all") {
+        solve_all(registry, inputs_dir)
+    } else {
+        run_one(registry, matches, inputs_dir, format)
+    };
+    match format {
+        Format::Text if matches.is_present("all") => print_table(&rows),
+        Format::Text => {}
+        _ => println!("{}", output::records(&rows, format)),
+    }
+    rows_exit_code(&rows)
+}
+
+/// Runs the requested parts of one day. With `Format::Text` the results are printed as they are,
+/// together with the timings; otherwise the caller prints them.
+fn run_one(registry: &Registry,
+           matches: &ArgMatches,
+           inputs_dir: &Path,
+           format: Format)
+           -> Vec<Row> {
+    let day = value_t!(matches, "day", u32).unwrap_or_else(|e| e.exit());
+    let parts = match matches.value_of("part") {
+        Some("both") => PARTS.to_vec(),
+        _ => vec![value_t!(matches, "part", Part).unwrap_or_else(|e| e.exit())],
+    };
+    let input = Input::from_matches(matches, day, inputs_dir);
+
+    let timer = Instant::now();
+
+    let rows = match registry.get(day) {
+        Ok(descriptor) => solve_day(descriptor, &parts, &input),
+        Err(error) => {
+            parts.iter()
+                .map(|&part| Row::failed(day, part, copy_error(&error), &input))
+                .collect()
+        }
+    };
+    if format != Format::Text {
+        return rows;
+    }
+
+    for row in &rows {
+        match row.result {
+            Ok(ref answer) if parts.len() > 1 => println!("Part {}: {}", row.part, answer),
+            Ok(ref answer) => println!("{}", answer),
+            Err(ref error) => {
+                if parts.len() > 1 {
+                    err_println!("Part {}:", row.part);
+                }
+                print_error(error);
+            }
+        }
+    }
+
+    let elapsed = timer.elapsed();
+    if let Some(parse_time) = rows.iter().filter_map(|row| row.parse_time).next() {
+        println!("Parse time: {} seconds", format_duration(parse_time));
+    }
+    for row in &rows {
+        if let Some(solve_time) = row.elapsed {
+            println!("Part {} time: {} seconds", row.part, format_duration(solve_time));
+        }
+    }
+    println!("Time elapsed: {} seconds", format_duration(elapsed));
+
+    rows
+}
+
+/// The outcome of running one part of one day.
+pub struct Row {
+    pub day: u32,
+    pub part: Part,
+    pub result: Result<Answer, Error>,
+    /// How long parsing the input for the day took, if it was parsed separately from solving.
+    pub parse_time: Option<Duration>,
+    /// How long solving the part took.
+    pub elapsed: Option<Duration>,
+    /// Where the input was read from.
+    pub input: String,
+}
+
+impl Row {
+    fn failed(day: u32, part: Part, error: Error, input: &Input) -> Row {
+        Row {
+            day,
+            part,
+            result: Err(error),
+            parse_time: None,
+            elapsed: None,
+            input: input.to_string(),
+        }
+    }
+}
+
+/// Prints a table of the results of running every part of every registered day. Parts that fail
+/// or have not been implemented are reported in the table without stopping the other parts from
+/// running.
+fn print_table(rows: &[Row]) {
+    let header = ["Day", "Part", "Answer", "Status", "Parse", "Solve"];
+    let seconds = |duration: Option<Duration>| {
+        duration.map_or("-".to_owned(), |d| format!("{}s", format_duration(d)))
+    };
+    let cells = rows.iter()
+        .map(|row| {
+            let (answer, status) = match row.result {
+                Ok(ref answer) => (format_answer(answer), "ok".to_owned()),
+                Err(ref error) => ("-".to_owned(), error.to_string()),
+            };
+            vec![row.day.to_string(),
+                 row.part.to_string(),
+                 answer,
+                 status,
+                 seconds(row.parse_time),
+                 seconds(row.elapsed)]
+        })
+        .collect::<Vec<_>>();
+    println!("{}", table::format_table(&header, &cells));
+}
+
+/// Parts that have not been implemented are expected, so only actual failures are reported
+/// through the exit code, unless none of the parts could be run at all.
+fn rows_exit_code(rows: &[Row]) -> i32 {
+    let errors = rows.iter().filter_map(|row| row.result.as_ref().err()).collect::<Vec<&Error>>();
+    match errors.iter().find(|error| !matches!(***error, Error::NotImplemented(_))) {
+        Some(error) => exit_code(error),
+        None if !rows.is_empty() && errors.len() == rows.len() => exit_code(errors[0]),
+        None => 0,
+    }
+}
+
+/// Solves every part of every registered day using the default input for that day in
+/// `inputs_dir`. Parts that have not been implemented are not run, and get a `NotImplemented`
+/// error as their result.
+pub fn solve_all(registry: &Registry, inputs_dir: &Path) -> Vec<Row> {
+    let mut rows = Vec::new();
+    for descriptor in registry.descriptors() {
+        let input = Input::default_for(descriptor.day, inputs_dir);
+        rows.extend(solve_day(descriptor, &PARTS, &input));
+    }
+    rows
+}
+
+/// Solves the given parts of one day, reading and parsing the input only once. Parts that have
+/// not been implemented are not run, and get a `NotImplemented` error as their result. If the
+/// input cannot be read or parsed, every implemented part gets that error as its result.
+fn solve_day(descriptor: &Descriptor, parts: &[Part], input: &Input) -> Vec<Row> {
+    let day = descriptor.day;
+    let implemented = parts.iter()
+        .cloned()
+        .filter(|&part| descriptor.implements(part))
+        .collect::<Vec<Part>>();
+
+    let mut solved = if implemented.is_empty() {
+        Vec::new()
+    } else {
+        let solver = descriptor.solver();
+        match input.read().and_then(|input| solver.solve_parts(&input, &implemented)) {
+            Ok(solved) => {
+                let parse_time = solved.parse_time;
+                solved.parts
+                    .into_iter()
+                    .map(|solved_part| {
+                        Row {
+                            day,
+                            part: solved_part.part,
+                            result: solved_part.result,
+                            parse_time,
+                            elapsed: Some(solved_part.elapsed),
+                            input: input.to_string(),
+                        }
+                    })
+                    .collect()
+            }
+            Err(error) => {
+                implemented.iter()
+                    .map(|&part| Row::failed(day, part, copy_error(&error), input))
+                    .collect()
+            }
+        }
+    };
+
+    let mut rows = Vec::with_capacity(parts.len());
+    for &part in parts {
+        if descriptor.implements(part) {
+            rows.push(solved.remove(0));
+        } else {
+            let error = Error::not_implemented(format!("day {} part {}", day, part));
+            rows.push(Row::failed(day, part, error, input));
+        }
+    }
+    rows
+}
+
+/// Errors cannot be cloned, since their sources are arbitrary errors. When one error affects
+/// several parts, each part gets a copy with the same kind and message, where the source is
+/// replaced by its message. I/O errors keep the kind of the underlying error.
+fn copy_error(error: &Error) -> Error {
+    let copy = match *error {
+        Error::Parse { ref message, line, column, .. } => {
+            Error::Parse {
+                message: message.clone(),
+                line,
+                column,
+                source: None,
+            }
+        }
+        Error::InvalidInput(ref message) => Error::invalid_input(message.clone()),
+        Error::NotImplemented(ref message) => Error::not_implemented(message.clone()),
+        Error::Internal { ref message, .. } => Error::internal(message.clone()),
+        Error::Io { ref path, ref source } => {
+            return Error::io(path, io::Error::new(source.kind(), source.to_string()))
+        }
+    };
+    match error.source() {
+        Some(source) => copy.with_source(source.to_string()),
+        None => copy,
+    }
+}
+
+/// Formats an answer so that it fits on one line, which keeps tables readable.
+pub fn format_answer(answer: &Answer) -> String {
+    answer.to_string().replace('\n', " ")
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    format!("{}.{:09}", duration.as_secs(), duration.subsec_nanos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use base::{ProblemSolver, Solution};
+
+    struct Solver;
+
+    impl Solution for Solver {
+        type Input = i64;
+
+        fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+            input.trim().parse::<i64>().map_err(|e| Error::from(e).at(1, 1))
+        }
+
+        fn part_one(&self, n: &Self::Input) -> Result<Answer, Error> {
+            Ok(Answer::from(n + 1))
+        }
+    }
+
+    fn get_solver() -> Box<dyn ProblemSolver> {
+        Box::new(Solver)
+    }
+
+    const DESCRIPTOR: Descriptor = Descriptor {
+        day: 7,
+        title: "Test",
+        parts: &[Part::One],
+        constructor: get_solver,
+    };
+
+    #[test]
+    fn test_solve_day() {
+        let rows = solve_day(&DESCRIPTOR, &PARTS, &Input::Literal("41".to_owned()));
+        assert_eq!(Answer::from(42), *rows[0].result.as_ref().unwrap());
+        assert!(rows[0].parse_time.is_some());
+        assert!(matches!(rows[1].result, Err(Error::NotImplemented(_))));
+        assert_eq!(0, rows_exit_code(&rows));
+    }
+
+    #[test]
+    fn test_solve_day_parse_error() {
+        let rows = solve_day(&DESCRIPTOR, &PARTS, &Input::Literal("x".to_owned()));
+        match rows[0].result {
+            Err(Error::Parse { line, column, .. }) => {
+                assert_eq!((Some(1), Some(1)), (line, column))
+            }
+            ref other => panic!("expected a parse error, got {:?}", other),
+        }
+        assert_eq!(65, rows_exit_code(&rows));
+    }
+
+    #[test]
+    fn test_rows_exit_code_only_not_implemented() {
+        let rows = solve_day(&DESCRIPTOR, &[Part::Two], &Input::Literal("1".to_owned()));
+        assert_eq!(69, rows_exit_code(&rows));
+    }
+
+    #[test]
+    fn test_copy_error() {
+        let error = Error::parse("bad").at(2, 3).with_source("cause".to_owned());
+        let copy = copy_error(&error);
+        assert_eq!(error.to_string(), copy.to_string());
+        assert_eq!("cause", copy.source().unwrap().to_string());
+    }
+}
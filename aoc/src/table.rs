@@ -0,0 +1,44 @@
+//! Formatting of plain text tables, used by the subcommands that report on several days at once.
+
+/// Formats the header and rows as left-aligned columns separated by two spaces. Trailing
+/// whitespace is removed from every line.
+pub fn format_table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths = header.iter().map(|h| h.chars().count()).collect::<Vec<usize>>();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |row: Vec<&str>| {
+        row.iter()
+            .zip(widths.iter())
+            .map(|(cell, &width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_owned()
+    };
+
+    let mut lines = vec![format_row(header.to_vec())];
+    lines.extend(rows.iter().map(|row| format_row(row.iter().map(String::as_str).collect())));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_table() {
+        let rows = vec![vec!["1".to_owned(), "long answer".to_owned(), "ok".to_owned()],
+                        vec!["10".to_owned(), "-".to_owned(), "failed".to_owned()]];
+        let expected = "Day  Answer       Status\n1    long answer  ok\n10   -            failed";
+        assert_eq!(expected, format_table(&["Day", "Answer", "Status"], &rows));
+    }
+
+    #[test]
+    fn test_format_table_no_rows() {
+        assert_eq!("A  B", format_table(&["A", "B"], &[]));
+    }
+}
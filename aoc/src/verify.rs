@@ -0,0 +1,252 @@
+//! The `verify` subcommand, which checks the answers of all solutions against a file of known
+//! answers. This makes it easy to check that a refactoring has not broken any solution.
+//!
+//! The known answers are stored in a TOML file with one table per day, e.g.
+//!
+//! ```toml
+//! [day01]
+//! part1 = "243"
+//! part2 = "142"
+//! ```
+
+use clap::ArgMatches;
+use toml::Value;
+
+use base::{Error, Part};
+use base::registry::Registry;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use {exit_code, json, print_error, table};
+use run::{self, Row};
+
+/// The name of the file with known answers, relative to the inputs directory.
+const ANSWERS_FILE: &str = "answers.toml";
+
+pub fn verify(registry: &Registry, matches: &ArgMatches, inputs_dir: &Path) -> i32 {
+    let path = matches.value_of("answers")
+        .map_or_else(|| inputs_dir.join(ANSWERS_FILE), |answers| Path::new(answers).to_path_buf());
+    let known = match KnownAnswers::load(&path) {
+        Ok(known) => known,
+        Err(error) => {
+            print_error(&error);
+            return exit_code(&error);
+        }
+    };
+
+    let rows = run::solve_all(registry, inputs_dir);
+    if matches.is_present("record") {
+        record(known, &rows, &path)
+    } else {
+        check(&known, &rows)
+    }
+}
+
+/// Compares every answer to the known answer and prints a table of the results. Returns a
+/// non-zero exit code if any answer is wrong or any solution failed.
+fn check(known: &KnownAnswers, rows: &[Row]) -> i32 {
+    let (mut matched, mut mismatched, mut missing, mut failed) = (0, 0, 0, 0);
+    let mut cells = Vec::with_capacity(rows.len());
+    for row in rows {
+        let expected = known.get(row.day, row.part);
+        let (answer, status) = match (row.result.as_ref(), expected) {
+            (Ok(answer), Some(expected)) if answer.matches(expected) => {
+                matched += 1;
+                (run::format_answer(answer), "ok".to_owned())
+            }
+            (Ok(answer), Some(_)) => {
+                mismatched += 1;
+                (run::format_answer(answer), "MISMATCH".to_owned())
+            }
+            (Ok(answer), None) => {
+                missing += 1;
+                (run::format_answer(answer), "missing".to_owned())
+            }
+            (Err(&Error::NotImplemented(_)), _) => ("-".to_owned(), "not implemented".to_owned()),
+            (Err(error), _) => {
+                failed += 1;
+                ("-".to_owned(), error.to_string())
+            }
+        };
+        let expected = expected.map_or("-".to_owned(), |e| e.trim().replace('\n', " "));
+        cells.push(vec![row.day.to_string(), row.part.to_string(), answer, expected, status]);
+    }
+
+    let header = ["Day", "Part", "Answer", "Expected", "Status"];
+    println!("{}", table::format_table(&header, &cells));
+    println!();
+    println!("{} matched, {} mismatched, {} missing, {} failed",
+             matched,
+             mismatched,
+             missing,
+             failed);
+
+    if mismatched > 0 || failed > 0 { 1 } else { 0 }
+}
+
+/// Stores every successfully computed answer as the known answer for that day and part. Known
+/// answers for parts that failed are kept as they are.
+fn record(mut known: KnownAnswers, rows: &[Row], path: &Path) -> i32 {
+    let mut recorded = 0;
+    let mut exit = 0;
+    for row in rows {
+        match row.result {
+            Ok(ref answer) => {
+                known.set(row.day, row.part, answer.to_string());
+                recorded += 1;
+            }
+            Err(Error::NotImplemented(_)) => {}
+            Err(ref error) => {
+                err_println!("Day {} part {} not recorded:", row.day, row.part);
+                print_error(error);
+                exit = exit_code(error);
+            }
+        }
+    }
+
+    if let Err(error) = known.save(path) {
+        print_error(&error);
+        return exit_code(&error);
+    }
+    println!("Recorded {} answers in {}", recorded, path.display());
+    exit
+}
+
+/// The known answers for each day and part, as text.
+#[derive(Debug, Default, PartialEq)]
+struct KnownAnswers {
+    answers: BTreeMap<(u32, Part), String>,
+}
+
+impl KnownAnswers {
+    /// Reads the known answers from `path`. A file that does not exist is treated as if it had no
+    /// answers in it, so that it can be created by recording.
+    fn load(path: &Path) -> Result<KnownAnswers, Error> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                KnownAnswers::parse(&contents).map_err(|e| {
+                    Error::parse(format!("could not parse answers file {}", path.display()))
+                        .with_source(e)
+                })
+            }
+            Err(ref error) if error.kind() == io::ErrorKind::NotFound => {
+                Ok(KnownAnswers::default())
+            }
+            Err(error) => Err(Error::io(path, error)),
+        }
+    }
+
+    fn parse(contents: &str) -> Result<KnownAnswers, Error> {
+        let value = contents.parse::<Value>()
+            .map_err(|e| Error::parse("invalid TOML").with_source(e))?;
+        let days = value.as_table().ok_or_else(|| Error::parse("expected a table of days"))?;
+
+        let mut answers = BTreeMap::new();
+        for (day_key, parts) in days {
+            let day = parse_day_key(day_key)?;
+            let parts = parts.as_table().ok_or_else(|| {
+                Error::parse(format!("expected a table of parts for {}", day_key))
+            })?;
+            for (part_key, answer) in parts {
+                let part = match part_key.as_str() {
+                    "part1" => Part::One,
+                    "part2" => Part::Two,
+                    _ => {
+                        return Err(Error::parse(format!("invalid part: {}.{}", day_key, part_key)))
+                    }
+                };
+                let answer = match *answer {
+                    Value::String(ref answer) => answer.clone(),
+                    Value::Integer(answer) => answer.to_string(),
+                    _ => {
+                        return Err(Error::parse(format!("answer for {}.{} is not a string or an \
+                                                         integer",
+                                                        day_key,
+                                                        part_key)))
+                    }
+                };
+                answers.insert((day, part), answer);
+            }
+        }
+        Ok(KnownAnswers { answers })
+    }
+
+    fn get(&self, day: u32, part: Part) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    fn set(&mut self, day: u32, part: Part, answer: String) {
+        self.answers.insert((day, part), answer);
+    }
+
+    fn to_toml(&self) -> String {
+        let mut toml = String::from("# Known answers, checked by `aoc verify`.\n\
+                                     # Update with `aoc verify --record`.\n");
+        let mut current_day = None;
+        for (&(day, part), answer) in &self.answers {
+            if current_day != Some(day) {
+                toml += &format!("\n[day{:02}]\n", day);
+                current_day = Some(day);
+            }
+            toml += &format!("part{} = {}\n", part, json::string(answer));
+        }
+        toml
+    }
+
+    fn save(&self, path: &Path) -> Result<(), Error> {
+        fs::write(path, self.to_toml()).map_err(|e| {
+            Error::internal(format!("could not write answers file {}", path.display()))
+                .with_source(e)
+        })
+    }
+}
+
+/// Parses a key such as `day01` into the day number.
+fn parse_day_key(key: &str) -> Result<u32, Error> {
+    if !key.starts_with("day") {
+        return Err(Error::parse(format!("invalid day: {}", key)));
+    }
+    key["day".len()..]
+        .parse::<u32>()
+        .map_err(|e| Error::parse(format!("invalid day: {}", key)).with_source(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let contents = "[day01]\npart1 = \"243\"\npart2 = 142\n\n[day04]\npart1 = \"DD483\"\n";
+        let known = KnownAnswers::parse(contents).unwrap();
+        assert_eq!(Some("243"), known.get(1, Part::One));
+        assert_eq!(Some("142"), known.get(1, Part::Two));
+        assert_eq!(Some("DD483"), known.get(4, Part::One));
+        assert_eq!(None, known.get(4, Part::Two));
+    }
+
+    #[test]
+    fn test_parse_err() {
+        let err_strs = ["[day01", "[dayone]\npart1 = \"1\"", "[day01]\npart3 = \"1\"",
+                        "[day01]\npart1 = 1.5", "day01 = 5"];
+        for err_str in &err_strs {
+            assert!(matches!(KnownAnswers::parse(err_str), Err(Error::Parse { .. })),
+                    "parse did not fail: {}",
+                    err_str);
+        }
+    }
+
+    #[test]
+    fn test_to_toml_roundtrip() {
+        let mut known = KnownAnswers::default();
+        known.set(2, Part::Two, "5DB3".to_owned());
+        known.set(1, Part::One, "5".to_owned());
+        known.set(10, Part::One, "#.\n.#\n\"quoted\" \\ \u{1}\u{7f}".to_owned());
+        let toml = known.to_toml();
+        assert!(toml.contains("[day01]\npart1 = \"5\"\n\n[day02]\npart2 = \"5DB3\"\n"));
+        assert_eq!(known, KnownAnswers::parse(&toml).unwrap());
+    }
+}
@@ -0,0 +1,204 @@
+//! This module contains the type that solutions use to return their answers, so that answers can
+//! be compared and formatted without first being converted to strings.
+
+use std::fmt;
+
+use Error;
+
+/// The answer to one part of a problem. Most answers are either numbers or short codes, but some
+/// problems draw their answer on a screen, which is represented by `Pixels`.
+///
+/// ```
+/// use base::Answer;
+///
+/// assert_eq!(Answer::Integer(1514), Answer::from(1514u32));
+/// assert_eq!("5DB3", Answer::from("5DB3").to_string());
+/// assert_eq!(Some(1514), Answer::from(1514).as_integer());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+    /// Rows of pixels, where `true` means that the pixel is lit.
+    Pixels(Vec<Vec<bool>>),
+}
+
+impl Answer {
+    /// Parses a picture where `#` is a lit pixel and `.` is an unlit pixel into a `Pixels` answer.
+    pub fn pixels_from_str(s: &str) -> Result<Answer, Error> {
+        let mut rows = Vec::new();
+        for (line_index, line) in s.lines().enumerate() {
+            let mut row = Vec::with_capacity(line.len());
+            for (column_index, c) in line.chars().enumerate() {
+                match c {
+                    '#' => row.push(true),
+                    '.' => row.push(false),
+                    _ => {
+                        return Err(Error::parse(format!("not a valid pixel: {}", c))
+                            .at(line_index + 1, column_index + 1))
+                    }
+                }
+            }
+            rows.push(row);
+        }
+        Ok(Answer::Pixels(rows))
+    }
+
+    /// Returns the value of an `Integer` answer, or `None` for other kinds of answers.
+    pub fn as_integer(&self) -> Option<i64> {
+        match *self {
+            Answer::Integer(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a `Text` answer, or `None` for other kinds of answers.
+    pub fn as_text(&self) -> Option<&str> {
+        match *self {
+            Answer::Text(ref text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Checks whether the text representation of this answer is the same as `expected`, ignoring
+    /// leading and trailing whitespace. This is useful when comparing against answers that have
+    /// been stored as text, e.g. in a file.
+    pub fn matches(&self, expected: &str) -> bool {
+        self.to_string().trim() == expected.trim()
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::Text(ref text) => write!(f, "{}", text),
+            Answer::Pixels(ref rows) => {
+                for (index, row) in rows.iter().enumerate() {
+                    if index > 0 {
+                        writeln!(f)?;
+                    }
+                    for &lit in row {
+                        write!(f, "{}", if lit { '#' } else { '.' })?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+macro_rules! impl_integer_answer {
+    ( $( $t:ty ),* ) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Answer {
+                    Answer::Integer(i64::from(value))
+                }
+            }
+
+            impl PartialEq<$t> for Answer {
+                fn eq(&self, other: &$t) -> bool {
+                    self.as_integer() == Some(i64::from(*other))
+                }
+            }
+        )*
+    }
+}
+
+impl_integer_answer!(i8, i16, i32, i64, u8, u16, u32);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Answer {
+        Answer::Text(text)
+    }
+}
+
+impl<'a> From<&'a str> for Answer {
+    fn from(text: &'a str) -> Answer {
+        Answer::Text(text.to_owned())
+    }
+}
+
+impl<'a> PartialEq<&'a str> for Answer {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_text() == Some(*other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_integer() {
+        assert_eq!("-12", Answer::Integer(-12).to_string());
+    }
+
+    #[test]
+    fn test_display_text() {
+        assert_eq!("5DB3", Answer::from("5DB3").to_string());
+    }
+
+    #[test]
+    fn test_display_pixels() {
+        let answer = Answer::Pixels(vec![vec![true, false, true], vec![false, true, false]]);
+        assert_eq!("#.#\n.#.", answer.to_string());
+    }
+
+    #[test]
+    fn test_pixels_from_str() {
+        let answer = Answer::pixels_from_str("#.#\n.#.\n").unwrap();
+        assert_eq!(Answer::Pixels(vec![vec![true, false, true], vec![false, true, false]]),
+                   answer);
+    }
+
+    #[test]
+    fn test_pixels_from_str_err() {
+        match Answer::pixels_from_str("#.#\n.x.") {
+            Err(Error::Parse { line, column, .. }) => {
+                assert_eq!(Some(2), line);
+                assert_eq!(Some(2), column);
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_from_integers() {
+        assert_eq!(Answer::Integer(5), Answer::from(5u8));
+        assert_eq!(Answer::Integer(5), Answer::from(5u32));
+        assert_eq!(Answer::Integer(-5), Answer::from(-5i64));
+    }
+
+    #[test]
+    fn test_eq_integer() {
+        assert!(Answer::Integer(5) == 5u32);
+        assert!(Answer::Integer(5) != 6i64);
+        assert!(Answer::from("5") != 5u32);
+    }
+
+    #[test]
+    fn test_eq_text() {
+        let answer = Answer::from("abc");
+        assert!(answer == "abc");
+        assert!(answer != "abd");
+        assert!(Answer::Integer(5) != "5");
+    }
+
+    #[test]
+    fn test_as_integer_and_text() {
+        assert_eq!(Some(5), Answer::Integer(5).as_integer());
+        assert_eq!(None, Answer::from("5").as_integer());
+        assert_eq!(Some("5"), Answer::from("5").as_text());
+        assert_eq!(None, Answer::Integer(5).as_text());
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(Answer::Integer(262).matches("262"));
+        assert!(Answer::Integer(262).matches(" 262\n"));
+        assert!(!Answer::Integer(262).matches("263"));
+        assert!(Answer::Pixels(vec![vec![true], vec![false]]).matches("#\n.\n"));
+    }
+}
@@ -1,39 +1,59 @@
 //! This module contains some useful functions, structs, and enum for working with discrete
 //! coordinates in a plane.
 
-use regex::Regex;
-
-use std::ops::Add;
+use std::cmp::Ordering;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
 use std::str::FromStr;
 
-use ::FromChar;
+use ::{Error, FromChar};
+
+mod compass;
+mod grid;
+pub mod hex;
+mod orientation;
+mod point;
+mod rect;
+mod segment;
+mod sparse;
+
+pub use self::compass::Compass;
+pub use self::grid::Grid;
+pub use self::orientation::{Orientation, YDown, YUp};
+pub use self::point::{Coordinate, Point, Point2, Point3, Point4};
+pub use self::rect::Rect;
+pub use self::segment::Segment;
+pub use self::sparse::SparseGrid;
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
 pub enum Turn {
     Left,
     Right,
+    /// Turning 180 degrees.
+    Around,
+    /// Not turning at all.
+    None,
 }
 
 impl FromChar for Turn {
-    type Err = String;
+    type Err = Error;
 
     fn from_char(c: char) -> Result<Self, Self::Err> {
         match c {
             'L' | 'l' => Ok(Turn::Left),
             'R' | 'r' => Ok(Turn::Right),
-            _ => Err(format!("not a valid turn: {}", c)),
+            _ => Err(Error::parse(format!("not a valid turn: {}", c))),
         }
     }
 }
 
 impl FromStr for Turn {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "L" | "l" => Ok(Turn::Left),
             "R" | "r" => Ok(Turn::Right),
-            _ => Err(format!("not a valid turn: {}", s)),
+            _ => Err(Error::parse(format!("not a valid turn: {}", s))),
         }
     }
 }
@@ -84,38 +104,46 @@ pub enum Direction {
 }
 
 impl FromChar for Direction {
-    type Err = String;
+    type Err = Error;
 
     fn from_char(c: char) -> Result<Self, Self::Err> {
         match c {
-            'U' | 'u' => Ok(Direction::Up),
-            'R' | 'r' => Ok(Direction::Right),
-            'D' | 'd' => Ok(Direction::Down),
-            'L' | 'l' => Ok(Direction::Left),
-            _ => Err(format!("not a valid direction: {}", c)),
+            'U' | 'u' | 'N' | 'n' => Ok(Direction::Up),
+            'R' | 'r' | 'E' | 'e' => Ok(Direction::Right),
+            'D' | 'd' | 'S' | 's' => Ok(Direction::Down),
+            'L' | 'l' | 'W' | 'w' => Ok(Direction::Left),
+            _ => Err(Error::parse(format!("not a valid direction: {}", c))),
         }
     }
 }
 
 impl FromStr for Direction {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "U" | "u" => Ok(Direction::Up),
-            "R" | "r" => Ok(Direction::Right),
-            "D" | "d" => Ok(Direction::Down),
-            "L" | "l" => Ok(Direction::Left),
-            _ => Err(format!("not a valid direction: {}", s)),
+            "U" | "u" | "N" | "n" => Ok(Direction::Up),
+            "R" | "r" | "E" | "e" => Ok(Direction::Right),
+            "D" | "d" | "S" | "s" => Ok(Direction::Down),
+            "L" | "l" | "W" | "w" => Ok(Direction::Left),
+            _ => Err(Error::parse(format!("not a valid direction: {}", s))),
         }
     }
 }
 
 impl Direction {
+    /// All directions, in clockwise order starting with `Up`.
+    pub const ALL: [Direction; 4] = [Direction::Up,
+                                     Direction::Right,
+                                     Direction::Down,
+                                     Direction::Left];
+
     pub fn turn(&self, turn: Turn) -> Direction {
         match turn {
             Turn::Right => self.turn_right(),
             Turn::Left => self.turn_left(),
+            Turn::Around => self.opposite(),
+            Turn::None => *self,
         }
     }
 
@@ -123,6 +151,40 @@ impl Direction {
         *self = self.turn(turn);
     }
 
+    /// Returns the turn that makes this direction face `other`, i.e.
+    /// `self.turn(self.turn_to(other)) == other`.
+    pub fn turn_to(&self, other: Direction) -> Turn {
+        if other == *self {
+            Turn::None
+        } else if other == self.turn_right() {
+            Turn::Right
+        } else if other == self.turn_left() {
+            Turn::Left
+        } else {
+            Turn::Around
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match *self {
+            Direction::Up => Direction::Down,
+            Direction::Right => Direction::Left,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+        }
+    }
+
+    /// The position that is one step away from the origin in this direction, e.g. `Position(0, 1)`
+    /// for `Up`. See [`Orientation`](trait.Orientation.html) for the other orientation.
+    pub fn unit_vector(&self) -> Position {
+        YUp::unit_vector(*self)
+    }
+
+    /// The direction whose unit vector is `vector`, or `None` if `vector` is not a unit vector.
+    pub fn from_unit_vector(vector: Position) -> Option<Direction> {
+        YUp::direction_of(vector)
+    }
+
     fn turn_right(&self) -> Direction {
         match *self {
             Direction::Up => Direction::Right,
@@ -148,7 +210,7 @@ mod direction_tests {
 
     #[test]
     fn test_parse_char_up() {
-        let up_chars = ['U', 'u'];
+        let up_chars = ['U', 'u', 'N', 'n'];
         for up_char in &up_chars {
             assert_eq!(Direction::Up, Direction::from_char(*up_char).unwrap());
         }
@@ -156,7 +218,7 @@ mod direction_tests {
 
     #[test]
     fn test_parse_str_up() {
-        let up_strs = ["U", "u"];
+        let up_strs = ["U", "u", "N", "n"];
         for up_str in &up_strs {
             assert_eq!(Direction::Up, Direction::from_str(up_str).unwrap());
         }
@@ -164,7 +226,7 @@ mod direction_tests {
 
     #[test]
     fn test_parse_char_right() {
-        let right_chars = ['R', 'r'];
+        let right_chars = ['R', 'r', 'E', 'e'];
         for right_char in &right_chars {
             assert_eq!(Direction::Right, Direction::from_char(*right_char).unwrap());
         }
@@ -172,7 +234,7 @@ mod direction_tests {
 
     #[test]
     fn test_parse_str_right() {
-        let right_strs = ["R", "r"];
+        let right_strs = ["R", "r", "E", "e"];
         for right_str in &right_strs {
             assert_eq!(Direction::Right, Direction::from_str(right_str).unwrap());
         }
@@ -180,7 +242,7 @@ mod direction_tests {
 
     #[test]
     fn test_parse_char_down() {
-        let down_chars = ['D', 'd'];
+        let down_chars = ['D', 'd', 'S', 's'];
         for down_char in &down_chars {
             assert_eq!(Direction::Down, Direction::from_char(*down_char).unwrap());
         }
@@ -188,7 +250,7 @@ mod direction_tests {
 
     #[test]
     fn test_parse_str_down() {
-        let down_strs = ["D", "d"];
+        let down_strs = ["D", "d", "S", "s"];
         for down_str in &down_strs {
             assert_eq!(Direction::Down, Direction::from_str(down_str).unwrap());
         }
@@ -196,7 +258,7 @@ mod direction_tests {
 
     #[test]
     fn test_parse_char_left() {
-        let left_chars = ['L', 'l'];
+        let left_chars = ['L', 'l', 'W', 'w'];
         for left_char in &left_chars {
             assert_eq!(Direction::Left, Direction::from_char(*left_char).unwrap());
         }
@@ -204,7 +266,7 @@ mod direction_tests {
 
     #[test]
     fn test_parse_str_left() {
-        let left_strs = ["L", "l"];
+        let left_strs = ["L", "l", "W", "w"];
         for left_str in &left_strs {
             assert_eq!(Direction::Left, Direction::from_str(left_str).unwrap());
         }
@@ -257,6 +319,50 @@ mod direction_tests {
         assert_eq!(new_direction, Direction::Up);
     }
 
+    #[test]
+    fn test_turn_around_and_none() {
+        for &direction in &Direction::ALL {
+            assert_eq!(direction.opposite(), direction.turn(Turn::Around));
+            assert_eq!(direction, direction.turn(Turn::None));
+        }
+    }
+
+    #[test]
+    fn test_turn_to() {
+        assert_eq!(Turn::Right, Direction::Up.turn_to(Direction::Right));
+        assert_eq!(Turn::Left, Direction::Up.turn_to(Direction::Left));
+        assert_eq!(Turn::Around, Direction::Left.turn_to(Direction::Right));
+        assert_eq!(Turn::None, Direction::Down.turn_to(Direction::Down));
+        for &from in &Direction::ALL {
+            for &to in &Direction::ALL {
+                assert_eq!(to, from.turn(from.turn_to(to)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_opposite() {
+        assert_eq!(Direction::Down, Direction::Up.opposite());
+        assert_eq!(Direction::Left, Direction::Right.opposite());
+        for &direction in &Direction::ALL {
+            assert_eq!(direction, direction.opposite().opposite());
+        }
+    }
+
+    #[test]
+    fn test_unit_vector() {
+        assert_eq!(Position(0, 1), Direction::Up.unit_vector());
+        assert_eq!(Position(1, 0), Direction::Right.unit_vector());
+        assert_eq!(Position(0, -1), Direction::Down.unit_vector());
+        assert_eq!(Position(-1, 0), Direction::Left.unit_vector());
+        for &direction in &Direction::ALL {
+            assert_eq!(Some(direction), Direction::from_unit_vector(direction.unit_vector()));
+        }
+        assert_eq!(None, Direction::from_unit_vector(Position(1, 1)));
+        assert_eq!(None, Direction::from_unit_vector(Position(0, 2)));
+        assert_eq!(None, Direction::from_unit_vector(Position(0, 0)));
+    }
+
     #[test]
     fn test_turn_mut_left() {
         let mut direction = Direction::Up;
@@ -271,34 +377,77 @@ mod direction_tests {
     }
 }
 
+/// A position in a plane. This is the two-dimensional [`Point`](struct.Point.html) with `i32`
+/// coordinates, given its own type so that the coordinates can be written as `Position(x, y)`.
+/// Parsing, arithmetic, ordering and distances are those of `Point2<i32>`.
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
 pub struct Position(pub i32, pub i32);
 
+/// Parses positions written like `(1, 2)`, in the same way as a `Point2<i32>`.
 impl FromStr for Position {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"^\( *(?P<x>-?\d+), *(?P<y>-?\d+) *\)$").unwrap();
-        }
+        Point2::from_str(s).map(Position::from)
+    }
+}
 
-        let captures = RE.captures(s).ok_or(format!("invalid position string: {}", s))?;
+impl Add for Position {
+    type Output = Position;
 
-        let x_str = captures.name("x").unwrap();
-        let x = i32::from_str(x_str).unwrap();
+    fn add(self, rhs: Position) -> Position {
+        Position::from(self.point() + rhs.point())
+    }
+}
 
-        let y_str = captures.name("y").unwrap();
-        let y = i32::from_str(y_str).unwrap();
+impl Sub for Position {
+    type Output = Position;
 
-        Ok(Position(x, y))
+    fn sub(self, rhs: Position) -> Position {
+        Position::from(self.point() - rhs.point())
     }
 }
 
-impl Add for Position {
+impl Neg for Position {
     type Output = Position;
 
-    fn add(self, rhs: Position) -> Position {
-        Position(self.0 + rhs.0, self.1 + rhs.1)
+    fn neg(self) -> Position {
+        Position::from(-self.point())
+    }
+}
+
+/// Scales the position as a vector from the origin.
+impl Mul<i32> for Position {
+    type Output = Position;
+
+    fn mul(self, rhs: i32) -> Position {
+        Position::from(self.point() * rhs)
+    }
+}
+
+impl AddAssign for Position {
+    fn add_assign(&mut self, rhs: Position) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Position {
+    fn sub_assign(&mut self, rhs: Position) {
+        *self = *self - rhs;
+    }
+}
+
+/// Positions are ordered row by row, i.e. first by `y` and then by `x`, so that iterating over a
+/// `BTreeMap` or `BTreeSet` of positions visits them in reading order.
+impl Ord for Position {
+    fn cmp(&self, other: &Position) -> Ordering {
+        self.point().cmp(&other.point())
+    }
+}
+
+impl PartialOrd for Position {
+    fn partial_cmp(&self, other: &Position) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
@@ -307,28 +456,44 @@ impl Position {
         Position(0, 0)
     }
 
-    pub fn walk_n(&self, direction: Direction, n: u32) -> Position {
-        let dx = match direction {
-            Direction::Right => 1,
-            Direction::Left => -1,
-            _ => 0,
-        } * n as i32;
+    /// The same position as a [`Point2`](type.Point2.html).
+    pub fn point(&self) -> Point2<i32> {
+        Point2::from(*self)
+    }
 
-        let dy = match direction {
-            Direction::Up => 1,
-            Direction::Down => -1,
-            _ => 0,
-        } * n as i32;
+    pub fn walk_n(&self, direction: Direction, n: u32) -> Position {
+        self.walk_n_in::<YUp>(direction, n)
+    }
 
-        let vector = Position(dx, dy);
+    /// Like [`walk_n`](#method.walk_n), but in the given [`Orientation`](trait.Orientation.html).
+    pub fn walk_n_in<O: Orientation>(&self, direction: Direction, n: u32) -> Position {
+        *self + O::unit_vector(direction) * n as i32
+    }
 
-        self.clone() + vector
+    /// Like [`walk`](#method.walk), but in the given [`Orientation`](trait.Orientation.html).
+    pub fn walk_in<O: Orientation>(&self, direction: Direction) -> Position {
+        self.walk_n_in::<O>(direction, 1)
     }
 
     pub fn walk(&self, direction: Direction) -> Position {
         self.walk_n(direction, 1)
     }
 
+    /// Takes `n` steps in `heading`, where a diagonal step changes both coordinates.
+    pub fn walk_heading_n(&self, heading: Compass, n: u32) -> Position {
+        *self + heading.unit_vector() * n as i32
+    }
+
+    pub fn walk_heading(&self, heading: Compass) -> Position {
+        self.walk_heading_n(heading, 1)
+    }
+
+    /// Like [`walk_heading`](#method.walk_heading), but in the given
+    /// [`Orientation`](trait.Orientation.html).
+    pub fn walk_heading_in<O: Orientation>(&self, heading: Compass) -> Position {
+        *self + heading.unit_vector_in::<O>()
+    }
+
     pub fn walk_n_mut(&mut self, direction: Direction, n: u32) {
         *self = self.walk_n(direction, n);
     }
@@ -337,8 +502,46 @@ impl Position {
         self.walk_n_mut(direction, 1);
     }
 
+    /// The Manhattan distance from the origin.
     pub fn taxi_distance(&self) -> u32 {
-        (self.0.abs() + self.1.abs()) as u32
+        self.manhattan_distance(Position::new())
+    }
+
+    /// The number of steps between the positions when only moving horizontally or vertically.
+    pub fn manhattan_distance(&self, other: Position) -> u32 {
+        self.point().manhattan_distance(other.point()) as u32
+    }
+
+    /// The number of steps between the positions when diagonal moves are also allowed.
+    pub fn chebyshev_distance(&self, other: Position) -> u32 {
+        self.point().chebyshev_distance(other.point()) as u32
+    }
+
+    /// Rotates the position as a vector about the origin, in the same way as a
+    /// [`Direction`](enum.Direction.html) is turned, e.g. `Position(0, 1)` turned right is
+    /// `Position(1, 0)`.
+    pub fn rotate(&self, turn: Turn) -> Position {
+        YUp::rotate(*self, turn)
+    }
+
+    /// Like [`rotate`](#method.rotate), but in the given [`Orientation`](trait.Orientation.html).
+    pub fn rotate_in<O: Orientation>(&self, turn: Turn) -> Position {
+        O::rotate(*self, turn)
+    }
+
+    /// Iterates over the four positions that are one step away in each direction, in the order of
+    /// [`Direction::ALL`](enum.Direction.html#associatedconstant.ALL).
+    pub fn neighbours4(&self) -> impl Iterator<Item = Position> {
+        let position = *self;
+        Direction::ALL.iter().map(move |&direction| position.walk(direction))
+    }
+
+    /// Iterates over the eight positions that surround this one, including the diagonal ones, in
+    /// the order of [`Compass::ALL`](enum.Compass.html#associatedconstant.ALL), i.e. clockwise
+    /// starting with the one that is one step up.
+    pub fn neighbours8(&self) -> impl Iterator<Item = Position> {
+        let position = *self;
+        Compass::ALL.iter().map(move |&heading| position.walk_heading(heading))
     }
 }
 
@@ -404,6 +607,28 @@ mod position_tests {
         }
     }
 
+    #[test]
+    fn test_parse_overflow() {
+        for err_str in &["(99999999999, 0)", "(0, -2147483649)"] {
+            match Position::from_str(err_str) {
+                Err(Error::Parse { .. }) => (),
+                other => panic!("expected a parse error for {}, got {:?}", err_str, other),
+            }
+        }
+        assert_eq!(Position(i32::MIN, i32::MAX), "(-2147483648, 2147483647)".parse().unwrap());
+    }
+
+    #[test]
+    fn test_parse_same_as_point() {
+        let strs = ["(1, 2)", "(1 ,2)", "( -1 , -2 )", "(1,2)", "(1, 2, 3)", "(1)", "(+1, 2)",
+                    "(1, 2", " (1, 2)", "(99999999999, 0)", "(, 2)"];
+        for s in &strs {
+            let point = s.parse::<Point2<i32>>().ok();
+            assert_eq!(point.map(Position::from), s.parse::<Position>().ok(), "{:?}", s);
+        }
+        assert_eq!(Position(1, 2), "(1 ,2)".parse().unwrap());
+    }
+
     #[test]
     fn test_add_positive() {
         let pos1 = Position(1, 2);
@@ -492,4 +717,75 @@ mod position_tests {
         let pos = Position(-2, -3);
         assert_eq!(pos.taxi_distance(), 5);
     }
+
+    #[test]
+    fn test_neighbours4() {
+        let neighbours = Position(2, -1).neighbours4().collect::<Vec<Position>>();
+        assert_eq!(vec![Position(2, 0), Position(3, -1), Position(2, -2), Position(1, -1)],
+                   neighbours);
+    }
+
+    #[test]
+    fn test_neighbours8() {
+        let neighbours = Position(0, 0).neighbours8().collect::<Vec<Position>>();
+        assert_eq!(8, neighbours.len());
+        assert_eq!(Position(0, 1), neighbours[0]);
+        assert_eq!(Position(1, 1), neighbours[1]);
+        for neighbour in Position(0, 0).neighbours4() {
+            assert!(neighbours.contains(&neighbour));
+        }
+        assert!(!neighbours.contains(&Position(0, 0)));
+    }
+
+    #[test]
+    fn test_sub_and_neg() {
+        assert_eq!(Position(-1, -1), Position(1, 2) - Position(2, 3));
+        assert_eq!(Position(-1, 2), -Position(1, -2));
+        assert_eq!(Position(0, 0), Position(4, -7) + -Position(4, -7));
+    }
+
+    #[test]
+    fn test_mul() {
+        assert_eq!(Position(3, -6), Position(1, -2) * 3);
+    }
+
+    #[test]
+    fn test_assign_ops() {
+        let mut pos = Position(1, 1);
+        pos += Position(2, -3);
+        assert_eq!(Position(3, -2), pos);
+        pos -= Position(3, 3);
+        assert_eq!(Position(0, -5), pos);
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Position(1, -2);
+        let b = Position(-3, 5);
+        assert_eq!(11, a.manhattan_distance(b));
+        assert_eq!(11, b.manhattan_distance(a));
+        assert_eq!(7, a.chebyshev_distance(b));
+        assert_eq!(0, a.chebyshev_distance(a));
+        assert_eq!(a.taxi_distance(), a.manhattan_distance(Position::new()));
+    }
+
+    #[test]
+    fn test_rotate_matches_turn() {
+        for &direction in &Direction::ALL {
+            for &turn in &[Turn::Left, Turn::Right, Turn::Around, Turn::None] {
+                assert_eq!(direction.turn(turn).unit_vector(),
+                           direction.unit_vector().rotate(turn));
+            }
+        }
+        assert_eq!(Position(2, -3), Position(3, 2).rotate(Turn::Right));
+        assert_eq!(Position(3, 2), Position(3, 2).rotate(Turn::Right).rotate(Turn::Left));
+    }
+
+    #[test]
+    fn test_ord_row_major() {
+        let mut positions = vec![Position(1, 1), Position(0, 1), Position(5, 0), Position(-1, 2)];
+        positions.sort();
+        assert_eq!(vec![Position(5, 0), Position(0, 1), Position(1, 1), Position(-1, 2)],
+                   positions);
+    }
 }
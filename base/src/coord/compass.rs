@@ -0,0 +1,230 @@
+//! Compass headings, for movement that can also go diagonally.
+
+use std::str::FromStr;
+
+use ::{Error, FromChar};
+use super::{Direction, Orientation, Position, Turn, YUp};
+
+/// One of the eight headings of a compass. The four cardinal headings are the same as the
+/// [`Direction`](enum.Direction.html)s, with `North` being `Up`.
+///
+/// ```
+/// use base::coord::{Compass, Direction, Position, Turn};
+///
+/// let heading = "NE".parse::<Compass>().unwrap();
+/// assert_eq!(Position(2, 2), Position(0, 0).walk_heading_n(heading, 2));
+/// assert_eq!(Compass::East, heading.turn_45(Turn::Right));
+/// assert_eq!(Compass::SouthEast, heading.turn(Turn::Right));
+/// assert_eq!(Some(Direction::Right), Compass::East.direction());
+/// assert_eq!(Compass::West, Compass::from(Direction::Left));
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum Compass {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+/// Parses the cardinal headings from the letters `N`, `E`, `S` and `W`, and all headings from
+/// arrows, i.e. `^`, `>`, `v` and `<` or the Unicode arrows such as `↗`.
+impl FromChar for Compass {
+    type Err = Error;
+
+    fn from_char(c: char) -> Result<Self, Self::Err> {
+        match c {
+            'N' | 'n' | '^' | '↑' => Ok(Compass::North),
+            '↗' => Ok(Compass::NorthEast),
+            'E' | 'e' | '>' | '→' => Ok(Compass::East),
+            '↘' => Ok(Compass::SouthEast),
+            'S' | 's' | 'v' | '↓' => Ok(Compass::South),
+            '↙' => Ok(Compass::SouthWest),
+            'W' | 'w' | '<' | '←' => Ok(Compass::West),
+            '↖' => Ok(Compass::NorthWest),
+            _ => Err(Error::parse(format!("not a valid compass heading: {}", c))),
+        }
+    }
+}
+
+/// Parses the abbreviated heading names such as `N` and `NE`, ignoring case, as well as anything
+/// that `from_char` accepts.
+impl FromStr for Compass {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let diagonals = [("NE", Compass::NorthEast),
+                         ("SE", Compass::SouthEast),
+                         ("SW", Compass::SouthWest),
+                         ("NW", Compass::NorthWest)];
+        let diagonal = diagonals.iter().find(|&&(name, _)| s.eq_ignore_ascii_case(name));
+        if let Some(&(_, heading)) = diagonal {
+            return Ok(heading);
+        }
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Compass::from_char(c),
+            _ => Err(Error::parse(format!("not a valid compass heading: {}", s))),
+        }
+    }
+}
+
+impl From<Direction> for Compass {
+    fn from(direction: Direction) -> Compass {
+        match direction {
+            Direction::Up => Compass::North,
+            Direction::Right => Compass::East,
+            Direction::Down => Compass::South,
+            Direction::Left => Compass::West,
+        }
+    }
+}
+
+impl Compass {
+    /// All headings, in clockwise order starting with `North`.
+    pub const ALL: [Compass; 8] = [Compass::North,
+                                   Compass::NorthEast,
+                                   Compass::East,
+                                   Compass::SouthEast,
+                                   Compass::South,
+                                   Compass::SouthWest,
+                                   Compass::West,
+                                   Compass::NorthWest];
+
+    /// The direction with the same heading, or `None` for the diagonal headings.
+    pub fn direction(&self) -> Option<Direction> {
+        match *self {
+            Compass::North => Some(Direction::Up),
+            Compass::East => Some(Direction::Right),
+            Compass::South => Some(Direction::Down),
+            Compass::West => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.direction().is_none()
+    }
+
+    /// Turns by 90 degrees for `Left` and `Right`, like a [`Direction`](enum.Direction.html).
+    pub fn turn(&self, turn: Turn) -> Compass {
+        self.rotate_eighths(match turn {
+            Turn::Right => 2,
+            Turn::Left => 6,
+            Turn::Around => 4,
+            Turn::None => 0,
+        })
+    }
+
+    /// Turns by 45 degrees for `Left` and `Right`. `Around` still turns by 180 degrees.
+    pub fn turn_45(&self, turn: Turn) -> Compass {
+        self.rotate_eighths(match turn {
+            Turn::Right => 1,
+            Turn::Left => 7,
+            Turn::Around => 4,
+            Turn::None => 0,
+        })
+    }
+
+    pub fn turn_mut(&mut self, turn: Turn) {
+        *self = self.turn(turn);
+    }
+
+    pub fn opposite(&self) -> Compass {
+        self.turn(Turn::Around)
+    }
+
+    /// The position that is one step away from the origin in this heading, e.g. `Position(1, 1)`
+    /// for `NorthEast`.
+    pub fn unit_vector(&self) -> Position {
+        self.unit_vector_in::<YUp>()
+    }
+
+    /// Like [`unit_vector`](#method.unit_vector), but in the given
+    /// [`Orientation`](trait.Orientation.html).
+    pub fn unit_vector_in<O: Orientation>(&self) -> Position {
+        let (first, second) = match *self {
+            Compass::NorthEast => (Direction::Up, Direction::Right),
+            Compass::SouthEast => (Direction::Down, Direction::Right),
+            Compass::SouthWest => (Direction::Down, Direction::Left),
+            Compass::NorthWest => (Direction::Up, Direction::Left),
+            cardinal => {
+                let direction = cardinal.direction().expect("not a diagonal heading");
+                return O::unit_vector(direction);
+            }
+        };
+        O::unit_vector(first) + O::unit_vector(second)
+    }
+
+    fn rotate_eighths(&self, eighths: usize) -> Compass {
+        let index = Compass::ALL.iter().position(|heading| heading == self).unwrap();
+        Compass::ALL[(index + eighths) % 8]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coord::YDown;
+
+    #[test]
+    fn test_parse_char() {
+        assert_eq!(Compass::North, Compass::from_char('n').unwrap());
+        assert_eq!(Compass::West, Compass::from_char('<').unwrap());
+        assert_eq!(Compass::South, Compass::from_char('v').unwrap());
+        assert_eq!(Compass::SouthWest, Compass::from_char('↙').unwrap());
+        for &c in &['U', 'x', ' ', 'V'] {
+            assert!(Compass::from_char(c).is_err(), "parse did not fail: {:?}", c);
+        }
+    }
+
+    #[test]
+    fn test_parse_str() {
+        assert_eq!(Compass::NorthWest, "NW".parse().unwrap());
+        assert_eq!(Compass::SouthEast, "se".parse().unwrap());
+        assert_eq!(Compass::East, "E".parse().unwrap());
+        assert_eq!(Compass::NorthEast, "↗".parse().unwrap());
+        assert_eq!(Compass::NorthEast, "Ne".parse().unwrap());
+        assert_eq!(Compass::SouthWest, "sW".parse().unwrap());
+        for err_str in &["", "NN", "EN", "north", "n e"] {
+            assert!(err_str.parse::<Compass>().is_err(), "parse did not fail: {}", err_str);
+        }
+    }
+
+    #[test]
+    fn test_direction_conversion() {
+        for &direction in &Direction::ALL {
+            let heading = Compass::from(direction);
+            assert!(!heading.is_diagonal());
+            assert_eq!(Some(direction), heading.direction());
+            assert_eq!(direction.unit_vector(), heading.unit_vector());
+            assert_eq!(Compass::from(direction.turn(Turn::Right)), heading.turn(Turn::Right));
+        }
+        assert_eq!(None, Compass::SouthWest.direction());
+    }
+
+    #[test]
+    fn test_turn() {
+        assert_eq!(Compass::NorthWest, Compass::North.turn_45(Turn::Left));
+        assert_eq!(Compass::West, Compass::NorthWest.turn_45(Turn::Left));
+        assert_eq!(Compass::NorthEast, Compass::NorthWest.turn(Turn::Right));
+        assert_eq!(Compass::SouthWest, Compass::NorthEast.opposite());
+        for &heading in &Compass::ALL {
+            assert_eq!(heading.turn(Turn::Right),
+                       heading.turn_45(Turn::Right).turn_45(Turn::Right));
+            assert_eq!(heading.turn(Turn::Around), heading.turn_45(Turn::Around));
+            assert_eq!(-heading.unit_vector(), heading.opposite().unit_vector());
+        }
+    }
+
+    #[test]
+    fn test_unit_vector() {
+        assert_eq!(Position(1, 1), Compass::NorthEast.unit_vector());
+        assert_eq!(Position(-1, -1), Compass::SouthWest.unit_vector());
+        assert_eq!(Position(1, -1), Compass::NorthEast.unit_vector_in::<YDown>());
+        assert_eq!(Position(0, 1), Compass::South.unit_vector_in::<YDown>());
+    }
+}
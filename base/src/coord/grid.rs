@@ -0,0 +1,447 @@
+//! A dense, rectangular grid of cells that is indexed by [`Position`](../struct.Position.html).
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use ::{Error, FromChar};
+use super::{Direction, Position, Rect, YDown};
+
+/// A rectangular grid of cells, stored row by row. The cell at `Position(x, y)` is in column `x`
+/// and row `y`, where `Position(0, 0)` is the top left cell. When a grid is parsed from text, row
+/// `y` is line `y + 1` of the text and column `x` is the character at column `x + 1`. This means
+/// that grids use the [`YDown`](enum.YDown.html) orientation, where `Direction::Up` is the
+/// previous row.
+///
+/// ```
+/// use base::coord::{Grid, Position};
+/// use base::FromChar;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Cell {
+///     Open,
+///     Wall,
+/// }
+///
+/// impl FromChar for Cell {
+///     type Err = base::Error;
+///
+///     fn from_char(c: char) -> Result<Self, Self::Err> {
+///         match c {
+///             '.' => Ok(Cell::Open),
+///             '#' => Ok(Cell::Wall),
+///             _ => Err(base::Error::parse(format!("invalid cell: {}", c))),
+///         }
+///     }
+/// }
+///
+/// let grid = "#.#\n...".parse::<Grid<Cell>>().unwrap();
+/// assert_eq!((3, 2), (grid.width(), grid.height()));
+/// assert_eq!(Some(&Cell::Wall), grid.get(Position(2, 0)));
+/// assert_eq!(None, grid.get(Position(3, 0)));
+/// assert_eq!(2, grid.neighbours(Position(0, 0)).count());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from cells given row by row. Fails with an `InvalidInput` error if the
+    /// number of cells does not match the size of the grid.
+    pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> Result<Grid<T>, Error> {
+        if cells.len() != width * height {
+            return Err(Error::invalid_input(format!("a {}x{} grid cannot have {} cells",
+                                                    width,
+                                                    height,
+                                                    cells.len())));
+        }
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Creates a grid where every cell has the same value.
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T>
+        where T: Clone
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The area covered by the grid, or `None` if the grid has no cells.
+    pub fn bounds(&self) -> Option<Rect> {
+        Rect::with_size(Position(0, 0), self.width as u32, self.height as u32)
+    }
+
+    /// Returns `true` if `position` is inside the grid.
+    pub fn contains(&self, position: Position) -> bool {
+        self.index_of(position).is_some()
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.index_of(position).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.index_of(position).map(move |index| &mut self.cells[index])
+    }
+
+    /// Returns row `y` as a slice, or `None` if there is no such row.
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        if y < self.height {
+            Some(&self.cells[y * self.width..(y + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over the rows from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        let width = self.width;
+        (0..self.height).map(move |y| &self.cells[y * width..(y + 1) * width])
+    }
+
+    /// Iterates over the cells in column `x` from top to bottom, or returns `None` if there is no
+    /// such column.
+    pub fn column(&self, x: usize) -> Option<impl Iterator<Item = &T>> {
+        if x < self.width {
+            Some(self.cells[x..].iter().step_by(self.width))
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over the columns from left to right, where each column is an iterator over its
+    /// cells from top to bottom.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.cells[x..].iter().step_by(self.width))
+    }
+
+    /// Iterates over all positions in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Position(x as i32, y as i32)))
+    }
+
+    /// Iterates over all cells together with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Returns the position of the first cell, row by row, for which `predicate` is true.
+    pub fn position<P>(&self, mut predicate: P) -> Option<Position>
+        where P: FnMut(&T) -> bool
+    {
+        self.iter().find(|&(_, cell)| predicate(cell)).map(|(position, _)| position)
+    }
+
+    /// Iterates over the cells that are directly above, below, left of and right of `position`,
+    /// in the order of [`Position::neighbours4`](struct.Position.html#method.neighbours4),
+    /// skipping those outside the grid.
+    pub fn neighbours(&self, position: Position) -> impl Iterator<Item = (Position, &T)> {
+        self.cells_at(position.neighbours4())
+    }
+
+    /// Returns the position one step away from `position` in `direction`, in the
+    /// [`YDown`](enum.YDown.html) orientation, or `None` if that is outside the grid.
+    pub fn step(&self, position: Position, direction: Direction) -> Option<Position> {
+        let next = position.walk_in::<YDown>(direction);
+        if self.contains(next) { Some(next) } else { None }
+    }
+
+    /// Like [`neighbours`](#method.neighbours), but also includes the diagonal neighbours, in the
+    /// order of [`Position::neighbours8`](struct.Position.html#method.neighbours8).
+    pub fn neighbours_with_diagonals(&self,
+                                     position: Position)
+                                     -> impl Iterator<Item = (Position, &T)> {
+        self.cells_at(position.neighbours8())
+    }
+
+    /// Creates a grid of the same size by applying `f` to every cell.
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+        where F: FnMut(&T) -> U
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn cells_at<'a, I>(&'a self, positions: I) -> impl Iterator<Item = (Position, &'a T)>
+        where I: Iterator<Item = Position> + 'a
+    {
+        positions.filter_map(move |position| self.get(position).map(|cell| (position, cell)))
+    }
+
+    fn index_of(&self, position: Position) -> Option<usize> {
+        let Position(x, y) = position;
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            None
+        } else {
+            Some(y as usize * self.width + x as usize)
+        }
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// Panics if `position` is outside the grid.
+    fn index(&self, position: Position) -> &T {
+        match self.index_of(position) {
+            Some(index) => &self.cells[index],
+            None => panic!("{:?} is outside the {}x{} grid", position, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        match self.index_of(position) {
+            Some(index) => &mut self.cells[index],
+            None => panic!("{:?} is outside the {}x{} grid", position, self.width, self.height),
+        }
+    }
+}
+
+/// Parses a grid with one row per line, where every character is parsed into a cell through
+/// `FromChar`. All lines must have the same length. Errors point to the line and column of the
+/// offending character.
+impl<T> FromStr for Grid<T>
+    where T: FromChar,
+          T::Err: Into<Error>
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for (y, line) in s.lines().enumerate() {
+            let mut line_width = 0;
+            for (x, c) in line.chars().enumerate() {
+                cells.push(T::from_char(c).map_err(|e| e.into().at(y + 1, x + 1))?);
+                line_width += 1;
+            }
+            match width {
+                None => width = Some(line_width),
+                Some(width) if width != line_width => {
+                    return Err(Error::parse(format!("expected {} cells but found {}",
+                                                    width,
+                                                    line_width))
+                        .at_line(y + 1));
+                }
+                _ => {}
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => {
+                Ok(Grid {
+                    width,
+                    height,
+                    cells,
+                })
+            }
+            _ => Err(Error::parse("the grid is empty")),
+        }
+    }
+}
+
+/// Renders the grid with one line per row, where each row is the cells rendered next to each
+/// other.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A cell that is just the digit that was parsed.
+    #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    struct Digit(u32);
+
+    impl FromChar for Digit {
+        type Err = Error;
+
+        fn from_char(c: char) -> Result<Self, Self::Err> {
+            c.to_digit(10).map(Digit).ok_or_else(|| Error::parse(format!("not a digit: {}", c)))
+        }
+    }
+
+    impl fmt::Display for Digit {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    fn grid() -> Grid<Digit> {
+        "123\n456\n".parse().unwrap()
+    }
+
+    fn digits<'a, I: Iterator<Item = &'a Digit>>(cells: I) -> Vec<u32> {
+        cells.map(|d| d.0).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = grid();
+        assert_eq!(3, grid.width());
+        assert_eq!(2, grid.height());
+        assert_eq!(Digit(1), grid[Position(0, 0)]);
+        assert_eq!(Digit(6), grid[Position(2, 1)]);
+    }
+
+    #[test]
+    fn test_parse_err_position() {
+        match "123\n4x6".parse::<Grid<Digit>>() {
+            Err(Error::Parse { line, column, .. }) => {
+                assert_eq!(Some(2), line);
+                assert_eq!(Some(2), column);
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_err_ragged() {
+        match "123\n45\n".parse::<Grid<Digit>>() {
+            Err(Error::Parse { line, .. }) => assert_eq!(Some(2), line),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_err_empty() {
+        assert!(matches!("".parse::<Grid<Digit>>(), Err(Error::Parse { .. })));
+        assert!(matches!("\n\n".parse::<Grid<Digit>>(), Err(Error::Parse { .. })));
+    }
+
+    #[test]
+    fn test_from_cells() {
+        let grid = Grid::from_cells(2, 2, vec![1, 2, 3, 4]).unwrap();
+        assert_eq!(Some(&3), grid.get(Position(0, 1)));
+        assert!(matches!(Grid::from_cells(2, 2, vec![1, 2, 3]), Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_bounds() {
+        let grid = grid();
+        assert!(grid.contains(Position(2, 1)));
+        assert_eq!(Some(Rect::new(Position(0, 0), Position(2, 1))), grid.bounds());
+        assert_eq!(None, Grid::filled(0, 3, 'x').bounds());
+        for &outside in &[Position(-1, 0), Position(0, -1), Position(3, 0), Position(0, 2)] {
+            assert!(!grid.contains(outside));
+            assert_eq!(None, grid.get(outside));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_outside() {
+        let _ = grid()[Position(3, 0)];
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut grid = grid();
+        *grid.get_mut(Position(1, 1)).unwrap() = Digit(0);
+        grid[Position(0, 0)] = Digit(9);
+        assert_eq!("923\n406", grid.to_string());
+        assert!(grid.get_mut(Position(5, 5)).is_none());
+    }
+
+    #[test]
+    fn test_rows() {
+        let grid = grid();
+        let rows = grid.rows().map(|row| digits(row.iter())).collect::<Vec<_>>();
+        assert_eq!(vec![vec![1, 2, 3], vec![4, 5, 6]], rows);
+        assert_eq!(vec![4, 5, 6], digits(grid.row(1).unwrap().iter()));
+        assert!(grid.row(2).is_none());
+    }
+
+    #[test]
+    fn test_columns() {
+        let grid = grid();
+        let columns = grid.columns().map(digits).collect::<Vec<_>>();
+        assert_eq!(vec![vec![1, 4], vec![2, 5], vec![3, 6]], columns);
+        assert_eq!(vec![3, 6], digits(grid.column(2).unwrap()));
+        assert!(grid.column(3).is_none());
+    }
+
+    #[test]
+    fn test_iter() {
+        let grid = grid();
+        let cells = grid.iter().map(|(p, d)| (p, d.0)).collect::<Vec<_>>();
+        assert_eq!((Position(0, 0), 1), cells[0]);
+        assert_eq!((Position(2, 0), 3), cells[2]);
+        assert_eq!((Position(0, 1), 4), cells[3]);
+        assert_eq!(Some(Position(1, 1)), grid.position(|d| d.0 == 5));
+        assert_eq!(None, grid.position(|d| d.0 == 7));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = grid();
+        let neighbours = grid.neighbours(Position(1, 0)).map(|(_, d)| d.0).collect::<Vec<_>>();
+        assert_eq!(vec![5, 3, 1], neighbours);
+        let corner = grid.neighbours(Position(0, 1)).map(|(p, _)| p).collect::<Vec<_>>();
+        assert_eq!(vec![Position(1, 1), Position(0, 0)], corner);
+    }
+
+    #[test]
+    fn test_step() {
+        let grid = grid();
+        assert_eq!(Some(Position(1, 0)), grid.step(Position(1, 1), Direction::Up));
+        assert_eq!(Some(Position(2, 1)), grid.step(Position(1, 1), Direction::Right));
+        assert_eq!(None, grid.step(Position(1, 1), Direction::Down));
+        assert_eq!(None, grid.step(Position(0, 0), Direction::Left));
+    }
+
+    #[test]
+    fn test_neighbours_with_diagonals() {
+        let grid = grid();
+        let neighbours = grid.neighbours_with_diagonals(Position(1, 1))
+            .map(|(_, d)| d.0)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![6, 3, 2, 1, 4], neighbours);
+    }
+
+    #[test]
+    fn test_map_and_display() {
+        let grid = grid().map(|d| if d.0 % 2 == 0 { '#' } else { '.' });
+        assert_eq!(".#.\n#.#", grid.to_string());
+        assert_eq!("..\n..\n..", Grid::filled(2, 3, '.').to_string());
+    }
+}
@@ -0,0 +1,370 @@
+//! Coordinates on a grid of flat-topped hexagons, where each cell has neighbours to the north and
+//! south but not to the east and west.
+//!
+//! Cells are addressed with axial coordinates `q` and `r`. Going north decreases `r`, going
+//! south-east increases `q`, and the implicit third cube coordinate is `s = -q - r`:
+//!
+//! ```
+//! use base::coord::Turn;
+//! use base::coord::hex::{self, Hex, HexDirection};
+//!
+//! let path = hex::parse_path("ne,ne,s,s").unwrap();
+//! let end = path.iter().fold(Hex::origin(), |hex, &direction| hex.walk(direction));
+//! assert_eq!(Hex::new(2, 0), end);
+//! assert_eq!(2, end.distance(Hex::origin()));
+//! assert_eq!(HexDirection::SouthEast, HexDirection::NorthEast.turn(Turn::Right));
+//! ```
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use Error;
+use super::Turn;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum HexDirection {
+    North,
+    NorthEast,
+    SouthEast,
+    South,
+    SouthWest,
+    NorthWest,
+}
+
+/// Parses the abbreviated direction names `n`, `ne`, `se`, `s`, `sw` and `nw`, in either case.
+impl FromStr for HexDirection {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "N" | "n" => Ok(HexDirection::North),
+            "NE" | "ne" => Ok(HexDirection::NorthEast),
+            "SE" | "se" => Ok(HexDirection::SouthEast),
+            "S" | "s" => Ok(HexDirection::South),
+            "SW" | "sw" => Ok(HexDirection::SouthWest),
+            "NW" | "nw" => Ok(HexDirection::NorthWest),
+            _ => Err(Error::parse(format!("not a valid hex direction: {}", s))),
+        }
+    }
+}
+
+impl HexDirection {
+    /// All directions, in clockwise order starting with `North`.
+    pub const ALL: [HexDirection; 6] = [HexDirection::North,
+                                        HexDirection::NorthEast,
+                                        HexDirection::SouthEast,
+                                        HexDirection::South,
+                                        HexDirection::SouthWest,
+                                        HexDirection::NorthWest];
+
+    /// Turns by 60 degrees for `Left` and `Right`, and by 180 degrees for `Around`.
+    pub fn turn(&self, turn: Turn) -> HexDirection {
+        let steps = match turn {
+            Turn::Right => 1,
+            Turn::Left => 5,
+            Turn::Around => 3,
+            Turn::None => 0,
+        };
+        HexDirection::ALL[(self.index() + steps) % 6]
+    }
+
+    pub fn turn_mut(&mut self, turn: Turn) {
+        *self = self.turn(turn);
+    }
+
+    pub fn opposite(&self) -> HexDirection {
+        self.turn(Turn::Around)
+    }
+
+    /// The cell that is one step away from the origin in this direction.
+    pub fn unit_vector(&self) -> Hex {
+        match *self {
+            HexDirection::North => Hex::new(0, -1),
+            HexDirection::NorthEast => Hex::new(1, -1),
+            HexDirection::SouthEast => Hex::new(1, 0),
+            HexDirection::South => Hex::new(0, 1),
+            HexDirection::SouthWest => Hex::new(-1, 1),
+            HexDirection::NorthWest => Hex::new(-1, 0),
+        }
+    }
+
+    fn index(&self) -> usize {
+        HexDirection::ALL.iter().position(|direction| direction == self).unwrap()
+    }
+}
+
+/// Parses a comma-separated list of directions such as `ne,ne,s`. Spaces around the directions
+/// are ignored, and errors report the column of the direction that could not be parsed.
+pub fn parse_path(s: &str) -> Result<Vec<HexDirection>, Error> {
+    let mut path = Vec::new();
+    let mut column = 1;
+    for part in s.trim_end_matches('\n').split(',') {
+        let direction = part.trim_matches(' ').parse().map_err(|e: Error| e.at(1, column))?;
+        path.push(direction);
+        column += part.len() + 1;
+    }
+    Ok(path)
+}
+
+/// A cell on a hex grid in axial coordinates. Like a [`Position`](../struct.Position.html), a
+/// cell can be used as a vector from the origin.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    pub fn new(q: i32, r: i32) -> Hex {
+        Hex { q, r }
+    }
+
+    pub fn origin() -> Hex {
+        Hex::new(0, 0)
+    }
+
+    /// Creates a cell from cube coordinates, which must add up to zero.
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Option<Hex> {
+        if q + r + s == 0 { Some(Hex::new(q, r)) } else { None }
+    }
+
+    /// The third cube coordinate.
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    pub fn cube(&self) -> (i32, i32, i32) {
+        (self.q, self.r, self.s())
+    }
+
+    pub fn walk(&self, direction: HexDirection) -> Hex {
+        *self + direction.unit_vector()
+    }
+
+    pub fn walk_n(&self, direction: HexDirection, n: u32) -> Hex {
+        *self + direction.unit_vector() * n as i32
+    }
+
+    pub fn walk_mut(&mut self, direction: HexDirection) {
+        *self = self.walk(direction);
+    }
+
+    /// The number of steps between the cells.
+    pub fn distance(&self, other: Hex) -> u32 {
+        let (q, r, s) = (*self - other).cube();
+        (q.unsigned_abs() + r.unsigned_abs() + s.unsigned_abs()) / 2
+    }
+
+    /// Rotates the cell about the origin, where `Right` is 60 degrees clockwise. This matches
+    /// `HexDirection::turn`, i.e. rotating a unit vector gives the unit vector of the turned
+    /// direction.
+    pub fn rotate(&self, turn: Turn) -> Hex {
+        let (q, r, s) = self.cube();
+        match turn {
+            Turn::Right => Hex::new(-r, -s),
+            Turn::Left => Hex::new(-s, -q),
+            Turn::Around => -*self,
+            Turn::None => *self,
+        }
+    }
+
+    /// Iterates over the six adjacent cells in the order of `HexDirection::ALL`.
+    pub fn neighbours(&self) -> impl Iterator<Item = Hex> {
+        let hex = *self;
+        HexDirection::ALL.iter().map(move |&direction| hex.walk(direction))
+    }
+
+    /// Iterates over the cells at exactly `radius` steps away, clockwise starting with the one due
+    /// north. A ring of radius 0 is just this cell.
+    pub fn ring(&self, radius: u32) -> impl Iterator<Item = Hex> {
+        let center = *self;
+        let count = if radius == 0 { 1 } else { 6 * radius };
+        (0..count).map(move |index| {
+            if radius == 0 {
+                return center;
+            }
+            // Each side of the ring starts at a corner and runs towards the next corner, which is
+            // two turns to the right of the direction of the corner itself.
+            let corner = HexDirection::ALL[(index / radius) as usize];
+            let side = corner.turn(Turn::Right).turn(Turn::Right);
+            center.walk_n(corner, radius).walk_n(side, index % radius)
+        })
+    }
+
+    /// Iterates over all cells at most `radius` steps away, ring by ring from the inside out.
+    pub fn spiral(&self, radius: u32) -> impl Iterator<Item = Hex> {
+        let hex = *self;
+        (0..radius + 1).flat_map(move |r| hex.ring(r))
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, rhs: Hex) -> Hex {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, rhs: Hex) -> Hex {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+
+    fn neg(self) -> Hex {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, rhs: i32) -> Hex {
+        Hex::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, rhs: Hex) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Hex {
+    fn sub_assign(&mut self, rhs: Hex) {
+        *self = *self - rhs;
+    }
+}
+
+impl fmt::Display for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.q, self.r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TURNS: [Turn; 4] = [Turn::Left, Turn::Right, Turn::Around, Turn::None];
+
+    #[test]
+    fn test_parse_direction() {
+        assert_eq!(HexDirection::North, "n".parse().unwrap());
+        assert_eq!(HexDirection::NorthEast, "NE".parse().unwrap());
+        assert_eq!(HexDirection::SouthWest, "sw".parse().unwrap());
+        for err_str in &["", "e", "w", "nn", "Ne", " n"] {
+            assert!(err_str.parse::<HexDirection>().is_err(), "parse did not fail: {}", err_str);
+        }
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(vec![HexDirection::NorthEast, HexDirection::South, HexDirection::NorthWest],
+                   parse_path("ne, s,nw\n").unwrap());
+        match parse_path("ne,se,x,s") {
+            Err(Error::Parse { line, column, .. }) => {
+                assert_eq!(Some(1), line);
+                assert_eq!(Some(7), column);
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        assert!(parse_path("").is_err());
+    }
+
+    #[test]
+    fn test_turn() {
+        assert_eq!(HexDirection::NorthEast, HexDirection::North.turn(Turn::Right));
+        assert_eq!(HexDirection::NorthWest, HexDirection::North.turn(Turn::Left));
+        assert_eq!(HexDirection::South, HexDirection::North.turn(Turn::Around));
+        for &direction in &HexDirection::ALL {
+            assert_eq!(direction, direction.opposite().opposite());
+            assert_eq!(-direction.unit_vector(), direction.opposite().unit_vector());
+            let mut turned = direction;
+            for _ in 0..6 {
+                turned.turn_mut(Turn::Right);
+            }
+            assert_eq!(direction, turned);
+        }
+    }
+
+    #[test]
+    fn test_rotate_matches_turn() {
+        for &direction in &HexDirection::ALL {
+            for &turn in &TURNS {
+                assert_eq!(direction.turn(turn).unit_vector(),
+                           direction.unit_vector().rotate(turn));
+            }
+        }
+    }
+
+    #[test]
+    fn test_cube() {
+        let hex = Hex::new(3, -5);
+        assert_eq!((3, -5, 2), hex.cube());
+        assert_eq!(Some(hex), Hex::from_cube(3, -5, 2));
+        assert_eq!(None, Hex::from_cube(3, -5, 3));
+    }
+
+    #[test]
+    fn test_distance() {
+        let origin = Hex::origin();
+        assert_eq!(3, origin.walk_n(HexDirection::SouthWest, 3).distance(origin));
+        assert_eq!(2, origin.walk(HexDirection::North).walk(HexDirection::SouthEast).distance(
+            origin.walk(HexDirection::South)));
+        let far = Hex::new(4, -1);
+        assert_eq!(4, far.distance(origin));
+        assert_eq!(far.distance(origin), origin.distance(far));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let hex = Hex::new(-2, 7);
+        let neighbours = hex.neighbours().collect::<Vec<_>>();
+        assert_eq!(6, neighbours.len());
+        assert!(neighbours.iter().all(|n| n.distance(hex) == 1));
+        assert_eq!(Hex::new(-2, 6), neighbours[0]);
+    }
+
+    #[test]
+    fn test_ring() {
+        let center = Hex::new(1, 1);
+        assert_eq!(vec![center], center.ring(0).collect::<Vec<_>>());
+        assert_eq!(center.neighbours().collect::<Vec<_>>(), center.ring(1).collect::<Vec<_>>());
+        for radius in 2..5 {
+            let mut ring = center.ring(radius).collect::<Vec<_>>();
+            assert_eq!(6 * radius as usize, ring.len());
+            assert_eq!(center.walk_n(HexDirection::North, radius), ring[0]);
+            assert!(ring.iter().all(|hex| hex.distance(center) == radius));
+            ring.sort();
+            ring.dedup();
+            assert_eq!(6 * radius as usize, ring.len());
+        }
+    }
+
+    #[test]
+    fn test_spiral() {
+        let cells = Hex::origin().spiral(3).collect::<Vec<_>>();
+        assert_eq!(1 + 6 + 12 + 18, cells.len());
+        assert_eq!(Hex::origin(), cells[0]);
+        assert!(cells.iter().all(|hex| hex.distance(Hex::origin()) <= 3));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mut hex = Hex::new(1, 2) + Hex::new(3, -1) * 2;
+        assert_eq!(Hex::new(7, 0), hex);
+        hex -= Hex::new(7, 1);
+        assert_eq!(Hex::new(0, -1), hex);
+        hex += -Hex::new(0, -1);
+        assert_eq!(Hex::origin(), hex);
+        assert_eq!("(2, -3)", Hex::new(2, -3).to_string());
+    }
+}
@@ -0,0 +1,118 @@
+//! The orientation of the `y` axis, which decides which way `Direction::Up` points.
+//!
+//! Puzzles that describe movement in a plane usually use the mathematical orientation, where `y`
+//! grows upwards. Puzzles that are given as text instead number the rows downwards, which is also
+//! how a [`Grid`](../struct.Grid.html) is indexed. The methods that depend on the orientation
+//! take it as a type parameter:
+//!
+//! ```
+//! use base::coord::{Direction, Position, YDown, YUp};
+//!
+//! let position = Position(3, 3);
+//! assert_eq!(Position(3, 4), position.walk_in::<YUp>(Direction::Up));
+//! assert_eq!(Position(3, 2), position.walk_in::<YDown>(Direction::Up));
+//! ```
+//!
+//! The methods without a type parameter, e.g. `Position::walk`, use [`YUp`](enum.YUp.html).
+
+use super::{Direction, Position, Turn};
+
+/// Decides how directions and turns map onto positions.
+pub trait Orientation {
+    /// The position that is one step away from the origin in `direction`.
+    fn unit_vector(direction: Direction) -> Position;
+
+    /// Rotates `vector` about the origin so that it points the way a direction would after the
+    /// turn, e.g. turning right is always clockwise when the plane is drawn in this orientation.
+    fn rotate(vector: Position, turn: Turn) -> Position;
+
+    /// The direction whose unit vector is `vector`, or `None` if `vector` is not a unit vector.
+    fn direction_of(vector: Position) -> Option<Direction> {
+        Direction::ALL.iter().cloned().find(|&direction| Self::unit_vector(direction) == vector)
+    }
+}
+
+/// The mathematical orientation, where `Up` means `y + 1`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum YUp {}
+
+/// The screen orientation, where `Up` means `y - 1`, i.e. the previous row of a text grid.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum YDown {}
+
+impl Orientation for YUp {
+    fn unit_vector(direction: Direction) -> Position {
+        match direction {
+            Direction::Up => Position(0, 1),
+            Direction::Right => Position(1, 0),
+            Direction::Down => Position(0, -1),
+            Direction::Left => Position(-1, 0),
+        }
+    }
+
+    fn rotate(vector: Position, turn: Turn) -> Position {
+        let Position(x, y) = vector;
+        match turn {
+            Turn::Right => Position(y, -x),
+            Turn::Left => Position(-y, x),
+            Turn::Around => Position(-x, -y),
+            Turn::None => vector,
+        }
+    }
+}
+
+impl Orientation for YDown {
+    fn unit_vector(direction: Direction) -> Position {
+        let Position(x, y) = YUp::unit_vector(direction);
+        Position(x, -y)
+    }
+
+    fn rotate(vector: Position, turn: Turn) -> Position {
+        // Mirroring the plane turns clockwise into counter-clockwise.
+        match turn {
+            Turn::Right => YUp::rotate(vector, Turn::Left),
+            Turn::Left => YUp::rotate(vector, Turn::Right),
+            _ => YUp::rotate(vector, turn),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TURNS: [Turn; 4] = [Turn::Left, Turn::Right, Turn::Around, Turn::None];
+
+    fn check_rotate_matches_turn<O: Orientation>() {
+        for &direction in &Direction::ALL {
+            for &turn in &TURNS {
+                assert_eq!(O::unit_vector(direction.turn(turn)),
+                           O::rotate(O::unit_vector(direction), turn));
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotate_matches_turn() {
+        check_rotate_matches_turn::<YUp>();
+        check_rotate_matches_turn::<YDown>();
+    }
+
+    #[test]
+    fn test_y_down() {
+        assert_eq!(Position(0, -1), YDown::unit_vector(Direction::Up));
+        assert_eq!(Position(0, 1), YDown::unit_vector(Direction::Down));
+        assert_eq!(Position(1, 0), YDown::unit_vector(Direction::Right));
+        assert_eq!(Position(0, 1), YDown::rotate(Position(1, 0), Turn::Right));
+    }
+
+    #[test]
+    fn test_direction_of() {
+        for &direction in &Direction::ALL {
+            assert_eq!(Some(direction), YUp::direction_of(YUp::unit_vector(direction)));
+            assert_eq!(Some(direction), YDown::direction_of(YDown::unit_vector(direction)));
+        }
+        assert_eq!(Some(Direction::Down), YDown::direction_of(Position(0, 1)));
+        assert_eq!(None, YDown::direction_of(Position(1, 1)));
+    }
+}
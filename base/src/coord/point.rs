@@ -0,0 +1,366 @@
+//! Points in any number of dimensions, for puzzles that are not set in a plane.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::Hash;
+use std::num::ParseIntError;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use Error;
+use super::Position;
+
+/// The integer types that can be used as coordinates of a [`Point`](struct.Point.html). It is
+/// implemented for all signed primitive integers.
+pub trait Coordinate
+    : Copy + Eq + Ord + Hash + fmt::Debug + fmt::Display + FromStr<Err = ParseIntError>
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Neg<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// The absolute value of `self - other`.
+    fn distance_to(self, other: Self) -> Self {
+        if self > other { self - other } else { other - self }
+    }
+}
+
+macro_rules! impl_coordinate {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                const ZERO: $t = 0;
+                const ONE: $t = 1;
+            }
+        )*
+    }
+}
+
+impl_coordinate!(i8, i16, i32, i64, i128, isize);
+
+/// A point with `N` coordinates of type `T`. Points are vectors from the origin, so they support
+/// the same arithmetic as [`Position`](struct.Position.html), which is the two-dimensional point
+/// that most solutions use.
+///
+/// ```
+/// use base::coord::{Point3, Position};
+///
+/// let a = "(1, -2, 3)".parse::<Point3<i64>>().unwrap();
+/// let b = Point3::new([0, 0, 1]);
+/// assert_eq!(Point3::new([1, -2, 2]), a - b);
+/// assert_eq!(5, a.manhattan_distance(b));
+/// assert_eq!(6, b.neighbours().count());
+/// assert_eq!(26, b.neighbours_with_diagonals().count());
+/// assert_eq!("(1, -2, 3)", a.to_string());
+/// ```
+///
+/// Like positions, points are ordered with the last coordinate being the most significant, so for
+/// two dimensions the order is row by row.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Point<T, const N: usize>(pub [T; N]);
+
+pub type Point2<T> = Point<T, 2>;
+pub type Point3<T> = Point<T, 3>;
+pub type Point4<T> = Point<T, 4>;
+
+impl<T: Coordinate, const N: usize> Point<T, N> {
+    pub fn new(coordinates: [T; N]) -> Point<T, N> {
+        Point(coordinates)
+    }
+
+    pub fn origin() -> Point<T, N> {
+        Point([T::ZERO; N])
+    }
+
+    pub fn coordinates(&self) -> &[T; N] {
+        &self.0
+    }
+
+    /// The number of steps between the points when only moving along one axis at a time.
+    pub fn manhattan_distance(&self, other: Point<T, N>) -> T {
+        self.0.iter().zip(other.0.iter()).fold(T::ZERO, |sum, (&a, &b)| sum + a.distance_to(b))
+    }
+
+    /// The number of steps between the points when diagonal moves are also allowed.
+    pub fn chebyshev_distance(&self, other: Point<T, N>) -> T {
+        self.0.iter().zip(other.0.iter()).map(|(&a, &b)| a.distance_to(b)).max().unwrap_or(T::ZERO)
+    }
+
+    /// Iterates over the `2 * N` points that are one step away along a single axis. For each axis
+    /// in order, the step in the negative direction comes before the step in the positive one.
+    pub fn neighbours(&self) -> impl Iterator<Item = Point<T, N>> {
+        let point = *self;
+        (0..N).flat_map(move |axis| {
+            IntoIterator::into_iter([-T::ONE, T::ONE]).map(move |delta| {
+                let mut neighbour = point;
+                neighbour.0[axis] = neighbour.0[axis] + delta;
+                neighbour
+            })
+        })
+    }
+
+    /// Iterates over the `3^N - 1` points that surround this one, including the diagonal ones, in
+    /// increasing order.
+    pub fn neighbours_with_diagonals(&self) -> impl Iterator<Item = Point<T, N>> {
+        let point = *self;
+        let count = 3usize.pow(N as u32);
+        (0..count).filter(move |&index| index != count / 2).map(move |index| {
+            // Each index is a number in base 3, where the digit for each axis is the offset + 1.
+            // The last axis is the most significant digit, which gives the order of `Point`.
+            let mut neighbour = point;
+            let mut rest = index;
+            for axis in 0..N {
+                let delta = match rest % 3 {
+                    0 => -T::ONE,
+                    1 => T::ZERO,
+                    _ => T::ONE,
+                };
+                neighbour.0[axis] = neighbour.0[axis] + delta;
+                rest /= 3;
+            }
+            neighbour
+        })
+    }
+}
+
+impl<T: Coordinate, const N: usize> Add for Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn add(mut self, rhs: Point<T, N>) -> Point<T, N> {
+        for (a, &b) in self.0.iter_mut().zip(rhs.0.iter()) {
+            *a = *a + b;
+        }
+        self
+    }
+}
+
+impl<T: Coordinate, const N: usize> Sub for Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn sub(self, rhs: Point<T, N>) -> Point<T, N> {
+        self + -rhs
+    }
+}
+
+impl<T: Coordinate, const N: usize> Neg for Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn neg(mut self) -> Point<T, N> {
+        for a in self.0.iter_mut() {
+            *a = -*a;
+        }
+        self
+    }
+}
+
+/// Scales the point as a vector from the origin.
+impl<T: Coordinate, const N: usize> Mul<T> for Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn mul(mut self, rhs: T) -> Point<T, N> {
+        for a in self.0.iter_mut() {
+            *a = *a * rhs;
+        }
+        self
+    }
+}
+
+impl<T: Coordinate, const N: usize> AddAssign for Point<T, N> {
+    fn add_assign(&mut self, rhs: Point<T, N>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Coordinate, const N: usize> SubAssign for Point<T, N> {
+    fn sub_assign(&mut self, rhs: Point<T, N>) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T, const N: usize> Index<usize> for Point<T, N> {
+    type Output = T;
+
+    fn index(&self, axis: usize) -> &T {
+        &self.0[axis]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for Point<T, N> {
+    fn index_mut(&mut self, axis: usize) -> &mut T {
+        &mut self.0[axis]
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for Point<T, N> {
+    fn cmp(&self, other: &Point<T, N>) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl<T: Ord, const N: usize> PartialOrd for Point<T, N> {
+    fn partial_cmp(&self, other: &Point<T, N>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Parses points written like `(1, 2, 3)`, where spaces are allowed around each coordinate.
+impl<T: Coordinate, const N: usize> FromStr for Point<T, N> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::parse(format!("invalid point string: {}", s));
+        if !s.starts_with('(') || !s.ends_with(')') || s.len() < 2 {
+            return Err(invalid());
+        }
+
+        let mut point = Point::origin();
+        let mut count = 0;
+        for (axis, coordinate) in s[1..s.len() - 1].split(',').enumerate() {
+            if axis >= N {
+                return Err(invalid());
+            }
+            let coordinate = coordinate.trim_matches(' ');
+            // Only plain integers are allowed, e.g. not `+1`.
+            let digits = coordinate.strip_prefix('-').unwrap_or(coordinate);
+            if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            point.0[axis] = coordinate.parse().map_err(|e| invalid().with_source(e))?;
+            count += 1;
+        }
+
+        if count == N { Ok(point) } else { Err(invalid()) }
+    }
+}
+
+impl<T: fmt::Display, const N: usize> fmt::Display for Point<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(")?;
+        for (axis, coordinate) in self.0.iter().enumerate() {
+            if axis > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", coordinate)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl From<Position> for Point2<i32> {
+    fn from(position: Position) -> Point2<i32> {
+        Point([position.0, position.1])
+    }
+}
+
+impl From<Point2<i32>> for Position {
+    fn from(point: Point2<i32>) -> Position {
+        Position(point.0[0], point.0[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Point([1, -2, 3]), "(1, -2, 3)".parse::<Point3<i32>>().unwrap());
+        assert_eq!(Point([1, 2, 3, 4]), "( 1,2,  3, 4 )".parse::<Point4<i8>>().unwrap());
+        assert_eq!(Point([0, 0]), "(-0, 0)".parse::<Point2<i64>>().unwrap());
+    }
+
+    #[test]
+    fn test_parse_err() {
+        let err_strs = ["(1, 2)", "(1, 2, 3, 4)", "1, 2, 3", "(1, 2, 3", "(1.0, 2, 3)", "(1,,3)",
+                        "(+1, 2, 3)", "(1, 2, 300)", "()", "(", " (1, 2, 3)"];
+        for err_str in &err_strs {
+            assert!(matches!(err_str.parse::<Point3<i8>>(), Err(Error::Parse { .. })),
+                    "parse did not fail: {}",
+                    err_str);
+        }
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        let point = Point([-7i64, 0, 12, 3]);
+        assert_eq!("(-7, 0, 12, 3)", point.to_string());
+        assert_eq!(point, point.to_string().parse().unwrap());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point([1, 2, 3]);
+        let b = Point([-1, 0, 5]);
+        assert_eq!(Point([0, 2, 8]), a + b);
+        assert_eq!(Point([2, 2, -2]), a - b);
+        assert_eq!(Point([-1, -2, -3]), -a);
+        assert_eq!(Point([3, 6, 9]), a * 3);
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(b, c);
+        c[2] = 10;
+        assert_eq!(10, c[2]);
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point([1i64, -2, 3, 0]);
+        let b = Point([-3, 5, 3, 1]);
+        assert_eq!(12, a.manhattan_distance(b));
+        assert_eq!(7, a.chebyshev_distance(b));
+        assert_eq!(0, a.manhattan_distance(a));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let neighbours = Point([0, 0, 0]).neighbours().collect::<Vec<_>>();
+        assert_eq!(vec![Point([-1, 0, 0]),
+                        Point([1, 0, 0]),
+                        Point([0, -1, 0]),
+                        Point([0, 1, 0]),
+                        Point([0, 0, -1]),
+                        Point([0, 0, 1])],
+                   neighbours);
+    }
+
+    #[test]
+    fn test_neighbours_with_diagonals() {
+        let point = Point([5, 5, 5, 5]);
+        let neighbours = point.neighbours_with_diagonals().collect::<Vec<_>>();
+        assert_eq!(80, neighbours.len());
+        assert!(!neighbours.contains(&point));
+        assert!(neighbours.iter().all(|n| n.chebyshev_distance(point) == 1));
+
+        let mut sorted = neighbours.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(neighbours, sorted);
+    }
+
+    #[test]
+    fn test_ord_matches_position() {
+        let positions = [Position(1, 1), Position(0, 1), Position(5, 0), Position(-1, 2)];
+        let mut sorted_positions = positions.to_vec();
+        sorted_positions.sort();
+        let mut sorted_points = positions.iter().map(|&p| Point2::from(p)).collect::<Vec<_>>();
+        sorted_points.sort();
+        let sorted_points = sorted_points.into_iter().map(Position::from).collect::<Vec<_>>();
+        assert_eq!(sorted_positions, sorted_points);
+    }
+
+    #[test]
+    fn test_position_conversion() {
+        let position = Position(3, -4);
+        let point = Point2::from(position);
+        assert_eq!(Point([3, -4]), point);
+        assert_eq!(position, Position::from(point));
+        assert_eq!(position.taxi_distance() as i32,
+                   point.manhattan_distance(Point::origin()));
+        let mut neighbours = point.neighbours().map(Position::from).collect::<Vec<_>>();
+        let mut expected = position.neighbours4().collect::<Vec<_>>();
+        neighbours.sort();
+        expected.sort();
+        assert_eq!(expected, neighbours);
+    }
+}
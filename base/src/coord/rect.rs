@@ -0,0 +1,208 @@
+//! Axis-aligned rectangular areas of the plane.
+
+use std::cmp;
+
+use super::Position;
+
+/// A rectangle of positions between two corners, inclusive, so a rectangle always contains at
+/// least one position. Operations that could produce an empty area return an `Option` instead.
+///
+/// ```
+/// use base::coord::{Position, Rect};
+///
+/// let rect = Rect::new(Position(0, 0), Position(2, 1));
+/// assert_eq!((3, 2, 6), (rect.width(), rect.height(), rect.area()));
+/// assert!(rect.contains(Position(2, 1)));
+/// assert_eq!(Position(2, 0), rect.clamp(Position(7, -3)));
+///
+/// let other = Rect::new(Position(2, 1), Position(5, 5));
+/// assert_eq!(Some(Rect::new(Position(2, 1), Position(2, 1))), rect.intersection(&other));
+/// assert_eq!(Rect::new(Position(0, 0), Position(5, 5)), rect.union(&other));
+///
+/// let positions = rect.positions().collect::<Vec<_>>();
+/// assert_eq!(Position(1, 0), positions[1]);
+/// assert_eq!(Some(rect), Rect::bounding_box(positions));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Rect {
+    min: Position,
+    max: Position,
+}
+
+impl Rect {
+    /// Creates the smallest rectangle that contains both corners, which can be given in any order.
+    pub fn new(a: Position, b: Position) -> Rect {
+        Rect {
+            min: Position(cmp::min(a.0, b.0), cmp::min(a.1, b.1)),
+            max: Position(cmp::max(a.0, b.0), cmp::max(a.1, b.1)),
+        }
+    }
+
+    /// Creates a rectangle with `min` as its smallest corner, or `None` if either side is 0.
+    pub fn with_size(min: Position, width: u32, height: u32) -> Option<Rect> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+        Some(Rect {
+            min,
+            max: Position(min.0 + (width - 1) as i32, min.1 + (height - 1) as i32),
+        })
+    }
+
+    /// The smallest rectangle that contains all the positions, or `None` if there are none.
+    pub fn bounding_box<I>(positions: I) -> Option<Rect>
+        where I: IntoIterator<Item = Position>
+    {
+        let mut positions = positions.into_iter();
+        let first = positions.next()?;
+        Some(positions.fold(Rect::new(first, first), |rect, position| rect.extend(position)))
+    }
+
+    /// The corner with the smallest coordinates.
+    pub fn min(&self) -> Position {
+        self.min
+    }
+
+    /// The corner with the largest coordinates, which is inside the rectangle.
+    pub fn max(&self) -> Position {
+        self.max
+    }
+
+    pub fn width(&self) -> u32 {
+        (self.max.0 - self.min.0) as u32 + 1
+    }
+
+    pub fn height(&self) -> u32 {
+        (self.max.1 - self.min.1) as u32 + 1
+    }
+
+    /// The number of positions in the rectangle.
+    pub fn area(&self) -> u64 {
+        u64::from(self.width()) * u64::from(self.height())
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        self.min.0 <= position.0 && position.0 <= self.max.0 && self.min.1 <= position.1 &&
+        position.1 <= self.max.1
+    }
+
+    /// The area that is in both rectangles, or `None` if they do not overlap.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let min = Position(cmp::max(self.min.0, other.min.0), cmp::max(self.min.1, other.min.1));
+        let max = Position(cmp::min(self.max.0, other.max.0), cmp::min(self.max.1, other.max.1));
+        if min.0 <= max.0 && min.1 <= max.1 {
+            Some(Rect { min, max })
+        } else {
+            None
+        }
+    }
+
+    /// The smallest rectangle that contains both rectangles.
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect::new(self.min, other.min).extend(self.max).extend(other.max)
+    }
+
+    /// The smallest rectangle that contains this rectangle and `position`.
+    pub fn extend(&self, position: Position) -> Rect {
+        Rect {
+            min: Position(cmp::min(self.min.0, position.0), cmp::min(self.min.1, position.1)),
+            max: Position(cmp::max(self.max.0, position.0), cmp::max(self.max.1, position.1)),
+        }
+    }
+
+    /// The position in the rectangle that is closest to `position`.
+    pub fn clamp(&self, position: Position) -> Position {
+        Position(position.0.max(self.min.0).min(self.max.0),
+                 position.1.max(self.min.1).min(self.max.1))
+    }
+
+    /// Iterates over all positions in the rectangle in row-major order, i.e. in the order of
+    /// `Position`.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let (min, max) = (self.min, self.max);
+        (min.1..max.1 + 1).flat_map(move |y| (min.0..max.0 + 1).map(move |x| Position(x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_normalises_corners() {
+        let rect = Rect::new(Position(3, -1), Position(-2, 4));
+        assert_eq!(Position(-2, -1), rect.min());
+        assert_eq!(Position(3, 4), rect.max());
+        assert_eq!(rect, Rect::new(Position(-2, 4), Position(3, -1)));
+        assert_eq!((6, 6, 36), (rect.width(), rect.height(), rect.area()));
+    }
+
+    #[test]
+    fn test_with_size() {
+        let rect = Rect::with_size(Position(1, 2), 3, 1).unwrap();
+        assert_eq!(Rect::new(Position(1, 2), Position(3, 2)), rect);
+        assert_eq!(None, Rect::with_size(Position(1, 2), 0, 1));
+        assert_eq!(None, Rect::with_size(Position(1, 2), 1, 0));
+    }
+
+    #[test]
+    fn test_contains() {
+        let rect = Rect::new(Position(0, 0), Position(2, 3));
+        assert!(rect.contains(Position(0, 0)));
+        assert!(rect.contains(Position(2, 3)));
+        assert!(rect.contains(Position(1, 2)));
+        assert!(!rect.contains(Position(3, 0)));
+        assert!(!rect.contains(Position(0, -1)));
+    }
+
+    #[test]
+    fn test_intersection() {
+        let a = Rect::new(Position(0, 0), Position(4, 4));
+        let b = Rect::new(Position(3, -2), Position(8, 1));
+        assert_eq!(Some(Rect::new(Position(3, 0), Position(4, 1))), a.intersection(&b));
+        assert_eq!(a.intersection(&b), b.intersection(&a));
+        assert_eq!(Some(a), a.intersection(&a));
+
+        let apart = Rect::new(Position(5, 0), Position(6, 4));
+        assert_eq!(None, a.intersection(&apart));
+    }
+
+    #[test]
+    fn test_union() {
+        let a = Rect::new(Position(0, 0), Position(1, 1));
+        let b = Rect::new(Position(-3, 5), Position(-2, 6));
+        assert_eq!(Rect::new(Position(-3, 0), Position(1, 6)), a.union(&b));
+        assert_eq!(a, a.union(&a));
+    }
+
+    #[test]
+    fn test_clamp() {
+        let rect = Rect::new(Position(-1, -1), Position(1, 1));
+        assert_eq!(Position(0, 0), rect.clamp(Position(0, 0)));
+        assert_eq!(Position(1, -1), rect.clamp(Position(5, -5)));
+        assert_eq!(Position(-1, 1), rect.clamp(Position(-2, 10)));
+    }
+
+    #[test]
+    fn test_positions() {
+        let rect = Rect::new(Position(1, 5), Position(2, 7));
+        let positions = rect.positions().collect::<Vec<_>>();
+        assert_eq!(vec![Position(1, 5),
+                        Position(2, 5),
+                        Position(1, 6),
+                        Position(2, 6),
+                        Position(1, 7),
+                        Position(2, 7)],
+                   positions);
+        assert_eq!(rect.area() as usize, positions.len());
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        assert_eq!(None, Rect::bounding_box(vec![]));
+        let positions = vec![Position(2, 2), Position(-1, 4), Position(0, -3)];
+        assert_eq!(Some(Rect::new(Position(-1, -3), Position(2, 4))),
+                   Rect::bounding_box(positions));
+    }
+}
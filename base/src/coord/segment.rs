@@ -0,0 +1,182 @@
+//! Axis-aligned line segments, for paths that are too long to store one position at a time.
+
+use super::{Direction, Position, Rect};
+
+/// A horizontal or vertical line of positions from `start` to `end`, inclusive. A segment can
+/// also be a single position.
+///
+/// ```
+/// use base::coord::{Direction, Position, Segment};
+///
+/// let horizontal = Segment::new(Position(-5, 2), Position(5, 2)).unwrap();
+/// let vertical = Segment::walk(Position(0, 0), Direction::Up, 100_000);
+/// assert_eq!(Some(Position(0, 2)), horizontal.crossing(&vertical));
+/// assert_eq!(None, Segment::new(Position(0, 0), Position(1, 1)));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Segment {
+    start: Position,
+    end: Position,
+}
+
+impl Segment {
+    /// Creates the segment between two positions, or `None` if they are not in the same row or
+    /// column.
+    pub fn new(start: Position, end: Position) -> Option<Segment> {
+        if start.0 == end.0 || start.1 == end.1 {
+            Some(Segment { start, end })
+        } else {
+            None
+        }
+    }
+
+    /// The segment that is walked by taking `n` steps from `start` in `direction`.
+    pub fn walk(start: Position, direction: Direction, n: u32) -> Segment {
+        Segment {
+            start,
+            end: start.walk_n(direction, n),
+        }
+    }
+
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    pub fn end(&self) -> Position {
+        self.end
+    }
+
+    /// The number of steps from `start` to `end`, which is one less than the number of positions.
+    pub fn length(&self) -> u32 {
+        // Computed in i64, since the coordinates can be up to the whole range of i32 apart.
+        let dx = i64::from(self.end.0) - i64::from(self.start.0);
+        let dy = i64::from(self.end.1) - i64::from(self.start.1);
+        (dx.unsigned_abs() + dy.unsigned_abs()) as u32
+    }
+
+    /// The direction from `start` to `end`, or `None` if the segment is a single position.
+    pub fn direction(&self) -> Option<Direction> {
+        let dx = self.end.0.cmp(&self.start.0) as i32;
+        let dy = self.end.1.cmp(&self.start.1) as i32;
+        Direction::from_unit_vector(Position(dx, dy))
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::new(self.start, self.end)
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        self.bounds().contains(position)
+    }
+
+    /// The positions that are on both segments, which is another segment when the segments
+    /// overlap, and a single position when they cross. The result runs in the same direction as
+    /// this segment, so its start is where this segment first meets `other`.
+    pub fn intersection(&self, other: &Segment) -> Option<Segment> {
+        // Both bounding boxes are one position wide, so they overlap exactly where the segments do.
+        let overlap = self.bounds().intersection(&other.bounds())?;
+        let (min, max) = (overlap.min(), overlap.max());
+        if self.start.manhattan_distance(min) <= self.start.manhattan_distance(max) {
+            Some(Segment { start: min, end: max })
+        } else {
+            Some(Segment { start: max, end: min })
+        }
+    }
+
+    /// The first position on this segment that is also on `other`, if there is any.
+    pub fn crossing(&self, other: &Segment) -> Option<Position> {
+        self.intersection(other).map(|intersection| intersection.start)
+    }
+
+    /// Iterates over the positions from `start` to `end`.
+    pub fn positions(&self) -> impl DoubleEndedIterator<Item = Position> {
+        let Position(x, y) = self.start;
+        let Position(dx, dy) =
+            self.direction().map_or(Position(0, 0), |direction| direction.unit_vector());
+        // The offsets are computed in i64, since they can be larger than i32::MAX.
+        (0..=i64::from(self.length())).map(move |n| {
+            Position((i64::from(x) + i64::from(dx) * n) as i32,
+                     (i64::from(y) + i64::from(dy) * n) as i32)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        assert!(Segment::new(Position(1, 2), Position(1, -7)).is_some());
+        assert!(Segment::new(Position(1, 2), Position(4, 2)).is_some());
+        assert!(Segment::new(Position(1, 2), Position(1, 2)).is_some());
+        assert_eq!(None, Segment::new(Position(1, 2), Position(2, 3)));
+    }
+
+    #[test]
+    fn test_walk() {
+        let segment = Segment::walk(Position(1, 1), Direction::Left, 3);
+        assert_eq!(Position(-2, 1), segment.end());
+        assert_eq!(3, segment.length());
+        assert_eq!(Some(Direction::Left), segment.direction());
+        assert_eq!(None, Segment::walk(Position(1, 1), Direction::Left, 0).direction());
+    }
+
+    #[test]
+    fn test_contains() {
+        let segment = Segment::walk(Position(0, 0), Direction::Down, 4);
+        assert!(segment.contains(Position(0, 0)));
+        assert!(segment.contains(Position(0, -4)));
+        assert!(!segment.contains(Position(0, 1)));
+        assert!(!segment.contains(Position(1, -2)));
+    }
+
+    #[test]
+    fn test_crossing() {
+        let a = Segment::walk(Position(0, 0), Direction::Right, 10);
+        let b = Segment::walk(Position(4, 3), Direction::Down, 5);
+        assert_eq!(Some(Position(4, 0)), a.crossing(&b));
+        assert_eq!(Some(Position(4, 0)), b.crossing(&a));
+
+        let c = Segment::walk(Position(4, 3), Direction::Down, 2);
+        assert_eq!(None, a.crossing(&c));
+
+        let touching = Segment::walk(Position(10, 0), Direction::Up, 2);
+        assert_eq!(Some(Position(10, 0)), a.crossing(&touching));
+    }
+
+    #[test]
+    fn test_intersection_overlapping() {
+        let a = Segment::walk(Position(0, 0), Direction::Right, 10);
+        let b = Segment::walk(Position(15, 0), Direction::Left, 10);
+        assert_eq!(Segment::new(Position(5, 0), Position(10, 0)), a.intersection(&b));
+        assert_eq!(Segment::new(Position(10, 0), Position(5, 0)), b.intersection(&a));
+        assert_eq!(Some(Position(10, 0)), b.crossing(&a));
+
+        let parallel = Segment::walk(Position(0, 1), Direction::Right, 10);
+        assert_eq!(None, a.intersection(&parallel));
+    }
+
+    #[test]
+    fn test_positions() {
+        let segment = Segment::walk(Position(2, 2), Direction::Down, 2);
+        assert_eq!(vec![Position(2, 2), Position(2, 1), Position(2, 0)],
+                   segment.positions().collect::<Vec<_>>());
+        let point = Segment::walk(Position(2, 2), Direction::Down, 0);
+        assert_eq!(vec![Position(2, 2)], point.positions().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_positions_whole_range() {
+        let segment = Segment::new(Position(i32::MAX, 0), Position(i32::MIN, 0)).unwrap();
+        assert_eq!(u32::MAX, segment.length());
+        assert_eq!(Some(Direction::Left), segment.direction());
+        assert_eq!(vec![Position(i32::MAX, 0), Position(i32::MAX - 1, 0)],
+                   segment.positions().take(2).collect::<Vec<_>>());
+        assert_eq!(Some(Position(i32::MIN, 0)), segment.positions().next_back());
+
+        let segment = Segment::new(Position(0, -1), Position(0, i32::MAX)).unwrap();
+        assert_eq!(i32::MAX as u32 + 1, segment.length());
+        assert_eq!(Some(Position(0, i32::MAX)), segment.positions().next_back());
+    }
+}
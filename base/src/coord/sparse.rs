@@ -0,0 +1,224 @@
+//! A grid over the whole plane that only stores the cells that have been set.
+
+use std::collections::hash_map::{self, HashMap};
+use std::fmt;
+
+use super::{Grid, Position, Rect};
+
+/// A grid without fixed bounds, where only the cells that have been inserted are stored. Every
+/// other cell has a default value. The grid keeps track of the bounding box of the inserted
+/// cells, so that the occupied region can be rendered or turned into a dense
+/// [`Grid`](struct.Grid.html).
+///
+/// ```
+/// use base::coord::{Position, Rect, SparseGrid};
+///
+/// let mut grid = SparseGrid::with_default('.');
+/// grid.insert(Position(-1, 0), '#');
+/// grid.insert(Position(1, 1), '#');
+/// assert_eq!(&'#', grid.get(Position(-1, 0)));
+/// assert_eq!(&'.', grid.get(Position(100, 100)));
+/// assert_eq!(Some(Rect::new(Position(-1, 0), Position(1, 1))), grid.bounds());
+/// assert_eq!("#..\n..#", grid.to_string());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Position, T>,
+    default: T,
+    bounds: Option<Rect>,
+}
+
+impl<T: Default> SparseGrid<T> {
+    /// Creates an empty grid where the default value of `T` is used for empty cells.
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::with_default(T::default())
+    }
+}
+
+impl<T: Default> Default for SparseGrid<T> {
+    fn default() -> SparseGrid<T> {
+        SparseGrid::new()
+    }
+}
+
+impl<T> SparseGrid<T> {
+    /// Creates an empty grid where `default` is used for empty cells.
+    pub fn with_default(default: T) -> SparseGrid<T> {
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    /// The number of occupied cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn is_occupied(&self, position: Position) -> bool {
+        self.cells.contains_key(&position)
+    }
+
+    /// Returns the value of the cell, which is the default value if the cell is empty.
+    pub fn get(&self, position: Position) -> &T {
+        self.cells.get(&position).unwrap_or(&self.default)
+    }
+
+    /// Returns the value of the cell, inserting the default value first if the cell is empty.
+    pub fn get_mut(&mut self, position: Position) -> &mut T
+        where T: Clone
+    {
+        self.extend_bounds(position);
+        let default = &self.default;
+        self.cells.entry(position).or_insert_with(|| default.clone())
+    }
+
+    /// Sets the value of the cell and returns the previous value if the cell was occupied.
+    pub fn insert(&mut self, position: Position, value: T) -> Option<T> {
+        self.extend_bounds(position);
+        self.cells.insert(position, value)
+    }
+
+    /// The bounding box of all occupied cells, or `None` if no cells are occupied.
+    pub fn bounds(&self) -> Option<Rect> {
+        self.bounds
+    }
+
+    /// Iterates over the occupied cells in arbitrary order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { inner: self.cells.iter() }
+    }
+
+    /// Copies the region inside the bounding box into a dense grid, where `Position(0, 0)` in the
+    /// dense grid is the smallest corner of the bounding box. Returns `None` if no cells are
+    /// occupied.
+    pub fn to_grid(&self) -> Option<Grid<T>>
+        where T: Clone
+    {
+        let bounds = self.bounds?;
+        let cells = bounds.positions().map(|position| self.get(position).clone()).collect();
+        Some(Grid::from_cells(bounds.width() as usize, bounds.height() as usize, cells)
+            .expect("the cells fill the bounding box"))
+    }
+
+    fn extend_bounds(&mut self, position: Position) {
+        self.bounds = Some(match self.bounds {
+            None => Rect::new(position, position),
+            Some(bounds) => bounds.extend(position),
+        });
+    }
+}
+
+/// Renders the region inside the bounding box in the same way as a [`Grid`](struct.Grid.html),
+/// i.e. with the row with the smallest `y` first. Empty cells are rendered as the default value,
+/// and an empty grid is rendered as an empty string.
+impl<T: fmt::Display> fmt::Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(bounds) = self.bounds {
+            let (min, max) = (bounds.min(), bounds.max());
+            for y in min.1..max.1 + 1 {
+                if y > min.1 {
+                    writeln!(f)?;
+                }
+                for x in min.0..max.0 + 1 {
+                    write!(f, "{}", self.get(Position(x, y)))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over the occupied cells of a [`SparseGrid`](struct.SparseGrid.html).
+pub struct Iter<'a, T: 'a> {
+    inner: hash_map::Iter<'a, Position, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (Position, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(&position, value)| (position, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        let grid = SparseGrid::<u32>::new();
+        assert!(grid.is_empty());
+        assert_eq!(None, grid.bounds());
+        assert_eq!(&0, grid.get(Position(3, -3)));
+        assert_eq!(None, grid.to_grid());
+        assert_eq!("", grid.to_string());
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(None, grid.insert(Position(2, -1), 5));
+        assert_eq!(Some(5), grid.insert(Position(2, -1), 6));
+        assert_eq!(1, grid.len());
+        assert!(grid.is_occupied(Position(2, -1)));
+        assert!(!grid.is_occupied(Position(0, 0)));
+        assert_eq!(&6, grid.get(Position(2, -1)));
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut grid = SparseGrid::new();
+        grid.insert(Position(0, 0), 1);
+        assert_eq!(Some(Rect::new(Position(0, 0), Position(0, 0))), grid.bounds());
+        grid.insert(Position(-3, 2), 1);
+        grid.insert(Position(1, -4), 1);
+        assert_eq!(Some(Rect::new(Position(-3, -4), Position(1, 2))), grid.bounds());
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut grid = SparseGrid::with_default(10);
+        *grid.get_mut(Position(-5, 5)) += 1;
+        *grid.get_mut(Position(-5, 5)) += 1;
+        assert_eq!(&12, grid.get(Position(-5, 5)));
+        assert_eq!(Some(Rect::new(Position(-5, 5), Position(-5, 5))), grid.bounds());
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut grid = SparseGrid::new();
+        grid.insert(Position(1, 1), 'a');
+        grid.insert(Position(-1, 0), 'b');
+        let mut cells = grid.iter().map(|(p, &c)| (p.0, p.1, c)).collect::<Vec<_>>();
+        cells.sort();
+        assert_eq!(vec![(-1, 0, 'b'), (1, 1, 'a')], cells);
+    }
+
+    #[test]
+    fn test_to_grid() {
+        let mut grid = SparseGrid::with_default('.');
+        grid.insert(Position(-1, -1), 'a');
+        grid.insert(Position(0, 1), 'b');
+        let dense = grid.to_grid().unwrap();
+        assert_eq!((2, 3), (dense.width(), dense.height()));
+        assert_eq!('a', dense[Position(0, 0)]);
+        assert_eq!('b', dense[Position(1, 2)]);
+        assert_eq!(grid.to_string(), dense.to_string());
+    }
+
+    #[test]
+    fn test_display() {
+        let mut grid = SparseGrid::with_default(' ');
+        for x in -2..3 {
+            grid.insert(Position(x, x.abs()), '*');
+        }
+        assert_eq!("  *  \n * * \n*   *", grid.to_string());
+    }
+}
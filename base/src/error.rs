@@ -0,0 +1,240 @@
+//! This module contains the error type that is shared by all solutions, so that users of the
+//! solutions (e.g. the `aoc` utility or the tests) can tell different kinds of failures apart
+//! instead of having to inspect error messages.
+
+use std::error;
+use std::fmt;
+use std::io;
+use std::num::ParseIntError;
+use std::path::{Path, PathBuf};
+
+/// The type of the underlying cause of an [`Error`](enum.Error.html), if there is one.
+pub type Source = Box<dyn error::Error + Send + Sync>;
+
+/// All the ways in which solving a problem can fail. Errors are usually created through the
+/// constructor functions, e.g.
+///
+/// ```
+/// use base::Error;
+///
+/// let error = Error::parse("not a valid turn: X").at(1, 4);
+/// assert_eq!("parse error at line 1, column 4: not a valid turn: X", error.to_string());
+/// ```
+#[derive(Debug)]
+pub enum Error {
+    /// The input could not be parsed. `line` and `column` are 1-based and point to where in the
+    /// input the error occurred, if that is known.
+    Parse {
+        message: String,
+        line: Option<usize>,
+        column: Option<usize>,
+        source: Option<Source>,
+    },
+    /// The input could be parsed, but it does not make sense for the problem, e.g. it has no
+    /// solution.
+    InvalidInput(String),
+    /// The requested solution (or part of it) has not been implemented yet.
+    NotImplemented(String),
+    /// Something went wrong inside the solution itself, which usually means that there is a bug.
+    Internal { message: String, source: Option<Source> },
+    /// A file could not be opened or read, e.g. because it does not exist or is not valid UTF-8.
+    Io { path: PathBuf, source: io::Error },
+}
+
+impl Error {
+    /// Creates a parse error without any information about where in the input it occurred.
+    pub fn parse<S: Into<String>>(message: S) -> Error {
+        Error::Parse {
+            message: message.into(),
+            line: None,
+            column: None,
+            source: None,
+        }
+    }
+
+    pub fn invalid_input<S: Into<String>>(message: S) -> Error {
+        Error::InvalidInput(message.into())
+    }
+
+    pub fn not_implemented<S: Into<String>>(message: S) -> Error {
+        Error::NotImplemented(message.into())
+    }
+
+    pub fn internal<S: Into<String>>(message: S) -> Error {
+        Error::Internal {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Creates an I/O error for a failure to open or read the file at `path`.
+    pub fn io<P: AsRef<Path>>(path: P, source: io::Error) -> Error {
+        Error::Io {
+            path: path.as_ref().to_path_buf(),
+            source,
+        }
+    }
+
+    /// Sets the line and column of a parse error. Errors of other kinds are returned unchanged.
+    pub fn at(self, line: usize, column: usize) -> Error {
+        match self {
+            Error::Parse { message, source, .. } => {
+                Error::Parse {
+                    message,
+                    line: Some(line),
+                    column: Some(column),
+                    source,
+                }
+            }
+            other => other,
+        }
+    }
+
+    /// Sets the line of a parse error, keeping the column if it is already known. This is useful
+    /// when a line has been parsed on its own and the error is propagated to the whole input.
+    /// Errors of other kinds are returned unchanged.
+    pub fn at_line(self, line: usize) -> Error {
+        match self {
+            Error::Parse { message, column, source, .. } => {
+                Error::Parse {
+                    message,
+                    line: Some(line),
+                    column,
+                    source,
+                }
+            }
+            other => other,
+        }
+    }
+
+    /// Attaches an underlying cause to a parse error or an internal error, which can later be
+    /// retrieved through `std::error::Error::source`. Errors of other kinds are returned unchanged.
+    pub fn with_source<E: Into<Source>>(self, cause: E) -> Error {
+        match self {
+            Error::Parse { message, line, column, .. } => {
+                Error::Parse {
+                    message,
+                    line,
+                    column,
+                    source: Some(cause.into()),
+                }
+            }
+            Error::Internal { message, .. } => {
+                Error::Internal {
+                    message,
+                    source: Some(cause.into()),
+                }
+            }
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Parse { ref message, line, column, .. } => {
+                match (line, column) {
+                    (Some(line), Some(column)) => {
+                        write!(f, "parse error at line {}, column {}: {}", line, column, message)
+                    }
+                    (Some(line), None) => write!(f, "parse error at line {}: {}", line, message),
+                    _ => write!(f, "parse error: {}", message),
+                }
+            }
+            Error::InvalidInput(ref message) => write!(f, "invalid input: {}", message),
+            Error::NotImplemented(ref message) => write!(f, "not implemented: {}", message),
+            Error::Internal { ref message, .. } => write!(f, "internal error: {}", message),
+            Error::Io { ref path, ref source } => {
+                write!(f, "could not read {}: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Parse { ref source, .. } |
+            Error::Internal { ref source, .. } => {
+                source.as_ref().map(|source| &**source as &(dyn error::Error + 'static))
+            }
+            Error::Io { ref source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(error: ParseIntError) -> Error {
+        Error::parse(error.to_string()).with_source(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error::Error as StdError;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_display_parse() {
+        assert_eq!("parse error: oops", Error::parse("oops").to_string());
+        assert_eq!("parse error at line 3: oops",
+                   Error::parse("oops").at_line(3).to_string());
+        assert_eq!("parse error at line 3, column 7: oops",
+                   Error::parse("oops").at(3, 7).to_string());
+    }
+
+    #[test]
+    fn test_display_other_kinds() {
+        assert_eq!("invalid input: oops", Error::invalid_input("oops").to_string());
+        assert_eq!("not implemented: oops", Error::not_implemented("oops").to_string());
+        assert_eq!("internal error: oops", Error::internal("oops").to_string());
+    }
+
+    #[test]
+    fn test_display_io() {
+        let source = io::Error::new(io::ErrorKind::NotFound, "no such file");
+        let error = Error::io("inputs/day01.input", source);
+        assert_eq!("could not read inputs/day01.input: no such file", error.to_string());
+        assert_eq!("no such file", error.source().unwrap().to_string());
+    }
+
+    #[test]
+    fn test_at_line_keeps_column() {
+        match Error::parse("oops").at(1, 5).at_line(4) {
+            Error::Parse { line, column, .. } => {
+                assert_eq!(Some(4), line);
+                assert_eq!(Some(5), column);
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_at_ignores_other_kinds() {
+        assert!(matches!(Error::invalid_input("oops").at(1, 1), Error::InvalidInput(_)));
+        assert!(matches!(Error::internal("oops").at_line(1), Error::Internal { .. }));
+    }
+
+    #[test]
+    fn test_source_chain() {
+        let cause = u32::from_str("abc").unwrap_err();
+        let error = Error::internal("could not count").with_source(cause.clone());
+        assert_eq!(cause.to_string(), error.source().unwrap().to_string());
+
+        let error = Error::parse("bad line").with_source(error);
+        let first = error.source().unwrap();
+        assert_eq!("internal error: could not count", first.to_string());
+        assert_eq!(cause.to_string(), first.source().unwrap().to_string());
+    }
+
+    #[test]
+    fn test_from_parse_int_error() {
+        let error = Error::from(u32::from_str("-1").unwrap_err());
+        assert!(matches!(error, Error::Parse { .. }));
+        assert!(error.source().is_some());
+    }
+}
@@ -0,0 +1,449 @@
+//! Sets of integers that are stored as ranges, so that huge ranges such as all `u32`s cost no more
+//! than a single number.
+//!
+//! ```
+//! use base::interval::{Interval, IntervalSet};
+//!
+//! let blocked = "5-8\n0-2\n4-7".parse::<IntervalSet<u32>>().unwrap();
+//! assert!(blocked.contains(6));
+//! assert!(!blocked.contains(3));
+//!
+//! let all = Interval::new(0, 9);
+//! let allowed = blocked.complement(all);
+//! assert_eq!(vec![Interval::new(3, 3), Interval::new(9, 9)], allowed.iter().collect::<Vec<_>>());
+//! assert_eq!(Some(3), blocked.gaps(all).next().map(|gap| gap.start()));
+//! assert_eq!(2, allowed.count());
+//! ```
+
+use std::fmt;
+use std::iter::FromIterator;
+use std::str::FromStr;
+
+use Error;
+
+/// The integer types that can be stored in an [`IntervalSet`](struct.IntervalSet.html). It is
+/// implemented for all primitive integers up to 64 bits, so that the number of values in a set
+/// always fits in a `u128`.
+pub trait Integer: Copy + Ord + fmt::Debug {
+    /// The next larger value, or `None` for the largest value of the type.
+    fn successor(self) -> Option<Self>;
+
+    /// The next smaller value, or `None` for the smallest value of the type.
+    fn predecessor(self) -> Option<Self>;
+
+    fn to_i128(self) -> i128;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn successor(self) -> Option<$t> {
+                    self.checked_add(1)
+                }
+
+                fn predecessor(self) -> Option<$t> {
+                    self.checked_sub(1)
+                }
+
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+            }
+        )*
+    }
+}
+
+impl_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// A non-empty range of integers from `start` to `end`, inclusive.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: Integer> Interval<T> {
+    /// Creates the interval from `start` to `end`, inclusive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is larger than `end`.
+    pub fn new(start: T, end: T) -> Interval<T> {
+        assert!(start <= end, "empty interval: {:?}-{:?}", start, end);
+        Interval { start, end }
+    }
+
+    /// The interval that only contains `value`.
+    pub fn single(value: T) -> Interval<T> {
+        Interval::new(value, value)
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    /// The number of values in the interval.
+    pub fn count(&self) -> u128 {
+        (self.end.to_i128() - self.start.to_i128()) as u128 + 1
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+}
+
+/// Parses intervals written like `5-8`. The end may be negative, e.g. `-5--3`.
+impl<T> FromStr for Interval<T>
+    where T: Integer + FromStr,
+          Error: From<T::Err>
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The first character is skipped, since it can be the sign of the start.
+        let separator = s.char_indices()
+            .skip(1)
+            .find(|&(_, c)| c == '-')
+            .map(|(index, _)| index)
+            .ok_or_else(|| Error::parse(format!("invalid interval: {:?}", s)))?;
+        let start = T::from_str(&s[..separator])?;
+        let end = T::from_str(&s[separator + 1..])?;
+        if start <= end {
+            Ok(Interval { start, end })
+        } else {
+            Err(Error::parse(format!("the interval is empty: {:?}", s)))
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// A set of integers, stored as the smallest number of intervals that cover exactly the values in
+/// the set. Overlapping and adjacent intervals are merged when they are inserted.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct IntervalSet<T> {
+    /// Sorted, with at least one value missing between any two intervals.
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet { intervals: Vec::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The number of values in the set.
+    pub fn count(&self) -> u128 {
+        self.intervals.iter().map(Interval::count).sum()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.intervals.partition_point(|interval| interval.end < value);
+        self.intervals.get(index).is_some_and(|interval| interval.start <= value)
+    }
+
+    /// Adds all values in `interval` to the set.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        // Intervals that overlap or touch the new one are merged into it.
+        let first = self.intervals
+            .partition_point(|other| is_gap_between(other.end, interval.start));
+        let last = self.intervals
+            .partition_point(|other| !is_gap_between(interval.end, other.start));
+        let mut merged = interval;
+        if first < last {
+            merged.start = merged.start.min(self.intervals[first].start);
+            merged.end = merged.end.max(self.intervals[last - 1].end);
+        }
+        self.intervals.splice(first..last, Some(merged));
+    }
+
+    /// Removes all values in `interval` from the set.
+    pub fn remove(&mut self, interval: Interval<T>) {
+        let first = self.intervals.partition_point(|other| other.end < interval.start);
+        let last = self.intervals.partition_point(|other| other.start <= interval.end);
+        if first >= last {
+            return;
+        }
+        let (lowest, highest) = (self.intervals[first], self.intervals[last - 1]);
+        let mut remaining = Vec::new();
+        if let Some(end) = interval.start.predecessor().filter(|&end| lowest.start <= end) {
+            remaining.push(Interval::new(lowest.start, end));
+        }
+        if let Some(start) = interval.end.successor().filter(|&start| start <= highest.end) {
+            remaining.push(Interval::new(start, highest.end));
+        }
+        self.intervals.splice(first..last, remaining);
+    }
+
+    /// Adds all values of `other` to the set.
+    pub fn merge(&mut self, other: &IntervalSet<T>) {
+        for &interval in &other.intervals {
+            self.insert(interval);
+        }
+    }
+
+    /// The values in `bounds` that are not in the set.
+    pub fn complement(&self, bounds: Interval<T>) -> IntervalSet<T> {
+        IntervalSet { intervals: self.gaps(bounds).collect() }
+    }
+
+    /// Iterates over the intervals in the set in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.intervals.iter().cloned()
+    }
+
+    /// Iterates over the ranges of values in `bounds` that are not in the set, in increasing
+    /// order. The first gap starts at the smallest such value.
+    pub fn gaps(&self, bounds: Interval<T>) -> impl Iterator<Item = Interval<T>> + '_ {
+        let first = self.intervals.partition_point(|interval| interval.end < bounds.start);
+        let mut next_start = Some(bounds.start);
+        let mut intervals = self.intervals[first..].iter();
+        ::std::iter::from_fn(move || {
+            loop {
+                let start = next_start.filter(|&start| start <= bounds.end)?;
+                match intervals.next() {
+                    Some(interval) if interval.start <= start => {
+                        next_start = interval.end.successor();
+                    }
+                    Some(interval) => {
+                        next_start = interval.end.successor();
+                        let end = interval.start.predecessor().unwrap().min(bounds.end);
+                        return Some(Interval::new(start, end));
+                    }
+                    None => {
+                        next_start = None;
+                        return Some(Interval::new(start, bounds.end));
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Returns `true` if there is at least one value after `end` and before `start`.
+fn is_gap_between<T: Integer>(end: T, start: T) -> bool {
+    end.successor().is_some_and(|next| next < start)
+}
+
+impl<T: Integer> Default for IntervalSet<T> {
+    fn default() -> IntervalSet<T> {
+        IntervalSet::new()
+    }
+}
+
+impl<T: Integer> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(intervals: I) -> IntervalSet<T> {
+        let mut set = IntervalSet::new();
+        for interval in intervals {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+/// Parses one interval per line, e.g. `5-8`. Errors report the line of the invalid interval.
+impl<T> FromStr for IntervalSet<T>
+    where T: Integer + FromStr,
+          Error: From<T::Err>
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut set = IntervalSet::new();
+        for (index, line) in s.lines().enumerate() {
+            set.insert(line.parse().map_err(|e: Error| e.at_line(index + 1))?);
+        }
+        Ok(set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(Interval::new(5, 8), "5-8".parse::<Interval<u32>>().unwrap());
+        assert_eq!(Interval::new(-5, -3), "-5--3".parse::<Interval<i32>>().unwrap());
+        assert_eq!(Interval::new(-5, 3), "-5-3".parse::<Interval<i64>>().unwrap());
+        assert_eq!(Interval::single(0), "0-0".parse::<Interval<u8>>().unwrap());
+        for err_str in &["", "5", "8-5", "5-", "-5", "a-b", "5-8-9", "0-256", " 5-8"] {
+            assert!(matches!(err_str.parse::<Interval<u8>>(), Err(Error::Parse { .. })),
+                    "parse did not fail: {:?}",
+                    err_str);
+        }
+        assert_eq!("-5--3", Interval::new(-5, -3).to_string());
+    }
+
+    #[test]
+    fn test_parse_set_err_line() {
+        match "1-2\n3-4\n6-5\n".parse::<IntervalSet<u32>>() {
+            Err(Error::Parse { line, .. }) => assert_eq!(Some(3), line),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let mut set = IntervalSet::new();
+        set.insert(Interval::new(10, 20));
+        set.insert(Interval::new(30, 40));
+        set.insert(Interval::new(21, 21));
+        assert_eq!(vec![Interval::new(10, 21), Interval::new(30, 40)],
+                   set.iter().collect::<Vec<_>>());
+        set.insert(Interval::new(22, 29));
+        assert_eq!(vec![Interval::new(10, 40)], set.iter().collect::<Vec<_>>());
+        set.insert(Interval::new(0, 5));
+        assert_eq!(2, set.iter().count());
+        assert_eq!(6 + 31, set.count());
+    }
+
+    #[test]
+    fn test_remove_splits() {
+        let mut set = IntervalSet::new();
+        set.insert(Interval::new(0, 100));
+        set.remove(Interval::new(10, 19));
+        set.remove(Interval::new(100, 200));
+        assert_eq!(vec![Interval::new(0, 9), Interval::new(20, 99)],
+                   set.iter().collect::<Vec<_>>());
+        set.remove(Interval::new(0, 99));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_extreme_values() {
+        let mut set = IntervalSet::new();
+        set.insert(Interval::new(u64::MAX - 1, u64::MAX));
+        set.insert(Interval::new(0, 1));
+        assert_eq!(4, set.count());
+        let everything = Interval::new(0, u64::MAX);
+        assert_eq!(u128::from(u64::MAX) - 3, set.complement(everything).count());
+        set.insert(everything);
+        assert_eq!(u128::from(u64::MAX) + 1, set.count());
+        assert_eq!(None, set.gaps(everything).next());
+        set.remove(Interval::single(u64::MAX));
+        set.remove(Interval::single(0));
+        assert_eq!(vec![Interval::new(1, u64::MAX - 1)], set.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a = "0-3\n10-12".parse::<IntervalSet<i32>>().unwrap();
+        let b = "-5--1\n4-9\n20-20".parse::<IntervalSet<i32>>().unwrap();
+        a.merge(&b);
+        assert_eq!(vec![Interval::new(-5, 12), Interval::single(20)],
+                   a.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_gaps_within_bounds() {
+        let set = "0-2\n5-8\n12-15".parse::<IntervalSet<u32>>().unwrap();
+        let gaps = set.gaps(Interval::new(1, 13)).collect::<Vec<_>>();
+        assert_eq!(vec![Interval::new(3, 4), Interval::new(9, 11)], gaps);
+        assert_eq!(vec![Interval::new(16, 20)],
+                   set.gaps(Interval::new(14, 20)).collect::<Vec<_>>());
+        assert_eq!(None, set.gaps(Interval::new(6, 7)).next());
+    }
+
+    /// A small pseudo-random number generator, so that the property tests are reproducible.
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn interval(&mut self) -> Interval<u8> {
+            let a = self.next() as u8;
+            // Mostly short intervals, so that the set does not fill up immediately.
+            let b = a.saturating_add((self.next() % 24) as u8);
+            Interval::new(a, b)
+        }
+    }
+
+    /// The same set as a plain array of flags, one for each `u8`.
+    fn bitset(set: &IntervalSet<u8>) -> [bool; 256] {
+        let mut bits = [false; 256];
+        for interval in set.iter() {
+            for value in interval.start()..=interval.end() {
+                bits[value as usize] = true;
+            }
+        }
+        bits
+    }
+
+    fn check_invariants(set: &IntervalSet<u8>) {
+        for pair in set.intervals.windows(2) {
+            assert!(is_gap_between(pair[0].end, pair[1].start), "not merged: {:?}", pair);
+        }
+    }
+
+    #[test]
+    fn test_against_bitset() {
+        let mut random = XorShift(0x2016_1224);
+        for _ in 0..200 {
+            let mut set = IntervalSet::new();
+            let mut bits = [false; 256];
+            for _ in 0..30 {
+                let interval = random.interval();
+                let insert = !random.next().is_multiple_of(3);
+                if insert {
+                    set.insert(interval);
+                } else {
+                    set.remove(interval);
+                }
+                for value in interval.start()..=interval.end() {
+                    bits[value as usize] = insert;
+                }
+
+                check_invariants(&set);
+                assert_eq!(&bits[..], &bitset(&set)[..]);
+                for value in 0..=255u8 {
+                    assert_eq!(bits[value as usize], set.contains(value));
+                }
+                let count = bits.iter().filter(|&&bit| bit).count();
+                assert_eq!(count as u128, set.count());
+
+                let bounds = random.interval();
+                let complement = set.complement(bounds);
+                check_invariants(&complement);
+                let complement_bits = bitset(&complement);
+                for value in 0..=255u8 {
+                    let expected = bounds.contains(value) && !bits[value as usize];
+                    assert_eq!(expected, complement_bits[value as usize], "value {}", value);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge_against_bitset() {
+        let mut random = XorShift(0x0104_0101);
+        for _ in 0..100 {
+            let a = (0..10).map(|_| random.interval()).collect::<IntervalSet<u8>>();
+            let b = (0..10).map(|_| random.interval()).collect::<IntervalSet<u8>>();
+            let mut merged = a.clone();
+            merged.merge(&b);
+            check_invariants(&merged);
+            let (a_bits, b_bits, merged_bits) = (bitset(&a), bitset(&b), bitset(&merged));
+            for value in 0..256 {
+                assert_eq!(a_bits[value] || b_bits[value], merged_bits[value]);
+            }
+        }
+    }
+}
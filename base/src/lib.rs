@@ -2,26 +2,39 @@
 //! problems, for instance by providing a trait that solutions should implement in order to be
 //! usable by the `aoc` utility.
 
-extern crate regex;
-
-#[macro_use]
-extern crate lazy_static;
-
 pub mod coord;
+pub mod interval;
+pub mod math;
+pub mod md5;
+pub mod registry;
+pub mod search;
 pub mod utils;
 
+mod answer;
+mod error;
+mod solution;
+
+pub use answer::Answer;
+pub use error::{Error, Source};
+pub use solution::{Solution, Solved, SolvedPart};
+
+use std::fmt;
 use std::str::FromStr;
+use std::time::Instant;
 
-/// Implementations of this trait should be able to solve Advent of Code problems. Usually an empty
-/// struct will suffice, e.g.
+/// Implementations of this trait should be able to solve Advent of Code problems. Most solutions
+/// should implement [`Solution`](trait.Solution.html) instead, which gives an implementation of
+/// this trait for free. Otherwise, an empty struct will usually suffice, e.g.
 ///
 /// ```
+/// # use base::Answer;
+/// # use base::Error;
 /// # use base::Part;
 /// # use base::ProblemSolver;
 /// struct Solver;
 ///
 /// impl ProblemSolver for Solver {
-///     fn solve(&self, input: &str, part: Part) -> Result<String, String> {
+///     fn solve(&self, input: &str, part: Part) -> Result<Answer, Error> {
 ///         // ...
 ///         # unimplemented!()
 ///     }
@@ -29,10 +42,34 @@ use std::str::FromStr;
 /// ```
 pub trait ProblemSolver {
     /// Solve the given [`Part`](enum.Part.html) of the problem using `input` as the input string.
-    /// If a solution is found, an `Ok` value containing the solution as a text representation
-    /// should be returned. If any error occurs, an `Err` value with a description of the error
-    /// should be returned.
-    fn solve(&self, input: &str, part: Part) -> Result<String, String>;
+    /// If a solution is found, an `Ok` value containing the solution as an
+    /// [`Answer`](enum.Answer.html) should be returned. If any error occurs, an `Err` value with an
+    /// [`Error`](enum.Error.html) of the appropriate kind should be returned.
+    fn solve(&self, input: &str, part: Part) -> Result<Answer, Error>;
+
+    /// Solve each of `parts` using the same input, and measure how long each part took. An `Err`
+    /// value is only returned if the input could not be parsed; errors from solving a part are
+    /// reported in the [`Solved`](struct.Solved.html) value instead.
+    ///
+    /// The default implementation calls `solve` for each part, which means that the input is
+    /// parsed once per part and that no parse time is reported.
+    fn solve_parts(&self, input: &str, parts: &[Part]) -> Result<Solved, Error> {
+        let parts = parts.iter()
+            .map(|&part| {
+                let timer = Instant::now();
+                let result = self.solve(input, part);
+                SolvedPart {
+                    part,
+                    result,
+                    elapsed: timer.elapsed(),
+                }
+            })
+            .collect();
+        Ok(Solved {
+            parse_time: None,
+            parts,
+        })
+    }
 }
 
 /// Exactly the same as `FromStr` found in the standard library. Types that implement `FromChar`
@@ -70,22 +107,40 @@ pub trait FromChar: Sized {
     fn from_char(c: char) -> Result<Self, Self::Err>;
 }
 
+/// Every character is accepted as is, e.g. for a `Grid<char>` of plain text.
+impl FromChar for char {
+    type Err = Error;
+
+    fn from_char(c: char) -> Result<Self, Self::Err> {
+        Ok(c)
+    }
+}
+
 /// A simple enum to represent either part 1 or part 2 of the problem, as all problems have two
 /// parts.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
 pub enum Part {
     One,
     Two,
 }
 
 impl FromStr for Part {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(part_str: &str) -> Result<Self, Self::Err> {
         match part_str {
             "1" => Ok(Part::One),
             "2" => Ok(Part::Two),
-            _ => Err(format!("invalid part specification: {}", part_str)),
+            _ => Err(Error::parse(format!("invalid part specification: {}", part_str))),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
         }
     }
 }
@@ -108,12 +163,19 @@ mod tests {
         assert_eq!(Part::from_str(part2_str).unwrap(), part2);
     }
 
+    #[test]
+    fn part_display_roundtrip() {
+        for part in &[Part::One, Part::Two] {
+            assert_eq!(*part, Part::from_str(&part.to_string()).unwrap());
+        }
+    }
+
     #[test]
     fn part_from_str_err() {
         let err_strs = ["", "one", "two", "01", "02", "-1", "-2", "3"];
         for err_str in &err_strs {
             let err = Part::from_str(err_str);
-            assert!(err.is_err());
+            assert!(matches!(err, Err(Error::Parse { .. })));
         }
     }
 }
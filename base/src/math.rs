@@ -0,0 +1,252 @@
+//! Number theory for puzzles about cycles and remainders.
+//!
+//! ```
+//! use base::math;
+//!
+//! assert_eq!(6, math::gcd(12, 18));
+//! assert_eq!(36, math::lcm(12, 18));
+//! assert_eq!(Some(4), math::mod_inverse(3, 11));
+//! assert_eq!(1, math::mod_pow(3, 10, 11));
+//!
+//! // t = 2 (mod 3), t = 3 (mod 5) and t = 2 (mod 7).
+//! assert_eq!(Some((23, 105)), math::crt(&[(2, 3), (3, 5), (2, 7)]));
+//! // t = 1 (mod 4) and t = 2 (mod 6) contradict each other.
+//! assert_eq!(None, math::crt(&[(1, 4), (2, 6)]));
+//! ```
+
+/// The greatest common divisor, which is never negative. `gcd(0, 0)` is 0.
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        let remainder = a % b;
+        a = b;
+        b = remainder;
+    }
+    a
+}
+
+/// The least common multiple, which is never negative. It is 0 if either number is 0.
+///
+/// # Panics
+///
+/// Panics if the result does not fit in an `i64`.
+pub fn lcm(a: i64, b: i64) -> i64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a / gcd(a, b)).checked_mul(b).expect("the least common multiple overflows i64").abs()
+}
+
+/// The least common multiple of all the numbers, e.g. the length of a cycle made up of several
+/// cycles. The least common multiple of no numbers is 1.
+pub fn lcm_all<I: IntoIterator<Item = i64>>(numbers: I) -> i64 {
+    numbers.into_iter().fold(1, lcm)
+}
+
+/// The extended Euclidean algorithm. Returns `(g, x, y)` such that `g = gcd(a, b)` and
+/// `a * x + b * y = g`.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The number `x` in `0..modulus` such that `a * x = 1 (mod modulus)`, or `None` if `a` and
+/// `modulus` are not coprime.
+///
+/// # Panics
+///
+/// Panics if `modulus` is not positive.
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    assert!(modulus > 0, "the modulus must be positive: {}", modulus);
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    if g == 1 { Some(x.rem_euclid(modulus)) } else { None }
+}
+
+/// `base` to the power of `exponent`, modulo `modulus`, in `0..modulus`.
+///
+/// # Panics
+///
+/// Panics if `modulus` is not positive.
+pub fn mod_pow(base: i64, mut exponent: u64, modulus: i64) -> i64 {
+    assert!(modulus > 0, "the modulus must be positive: {}", modulus);
+    let modulus = i128::from(modulus);
+    let mut base = i128::from(base).rem_euclid(modulus);
+    let mut result = 1 % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    result as i64
+}
+
+/// Solves a system of congruences `t = residue (mod modulus)`, given as `(residue, modulus)`
+/// pairs, using the Chinese Remainder Theorem. The moduli do not have to be coprime.
+///
+/// Returns `(t, m)`, where `t` in `0..m` is the smallest non-negative solution and `m` is the
+/// least common multiple of the moduli, so every solution is `t + k * m`. Returns `None` if the
+/// congruences contradict each other. No congruences at all are solved by `(0, 1)`.
+///
+/// # Panics
+///
+/// Panics if a modulus is not positive, or if the least common multiple of the moduli does not
+/// fit in an `i64`.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut solution = 0i128;
+    let mut combined = 1i128;
+    for &(residue, modulus) in congruences {
+        assert!(modulus > 0, "the modulus must be positive: {}", modulus);
+        let (residue, modulus) = (i128::from(residue).rem_euclid(i128::from(modulus)),
+                                  i128::from(modulus));
+
+        // Find k such that solution + k * combined = residue (mod modulus), which is only
+        // possible if the difference is a multiple of gcd(combined, modulus).
+        let g = i128::from(gcd(combined as i64, modulus as i64));
+        let difference = residue - solution;
+        if difference % g != 0 {
+            return None;
+        }
+        let reduced_modulus = modulus / g;
+        let inverse = i128::from(mod_inverse((combined / g) as i64, reduced_modulus as i64)
+            .expect("the reduced moduli are coprime"));
+        let k = (difference / g).rem_euclid(reduced_modulus) * inverse % reduced_modulus;
+
+        let next_combined = combined * reduced_modulus;
+        assert!(next_combined <= i128::from(i64::MAX),
+                "the least common multiple of the moduli overflows i64");
+        solution = (solution + k * combined).rem_euclid(next_combined);
+        combined = next_combined;
+    }
+    Some((solution as i64, combined as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm_brute_force() {
+        for a in -30i64..31 {
+            for b in -30i64..31 {
+                let expected_gcd = (1..31).rev()
+                    .find(|d| a % d == 0 && b % d == 0)
+                    .map_or(0, |d| if a == 0 && b == 0 { 0 } else { d });
+                assert_eq!(expected_gcd, gcd(a, b), "gcd({}, {})", a, b);
+
+                let expected_lcm = if a == 0 || b == 0 {
+                    0
+                } else {
+                    (1..).find(|m| m % a == 0 && m % b == 0).unwrap()
+                };
+                assert_eq!(expected_lcm, lcm(a, b), "lcm({}, {})", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lcm_all() {
+        assert_eq!(1, lcm_all(vec![]));
+        assert_eq!(60, lcm_all(vec![2, 3, 4, 5, 6]));
+        assert_eq!(2 * 3 * 5 * 7 * 11 * 13 * 17 * 19, lcm_all(vec![2, 3, 5, 7, 11, 13, 17, 19]));
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for a in -25i64..26 {
+            for b in -25i64..26 {
+                let (g, x, y) = extended_gcd(a, b);
+                assert_eq!(gcd(a, b), g, "extended_gcd({}, {})", a, b);
+                assert_eq!(g, a * x + b * y, "extended_gcd({}, {})", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mod_inverse_brute_force() {
+        for modulus in 1i64..30 {
+            for a in -40i64..40 {
+                let expected = (0..modulus).find(|x| (a * x).rem_euclid(modulus) == 1 % modulus);
+                assert_eq!(expected, mod_inverse(a, modulus), "mod_inverse({}, {})", a, modulus);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mod_pow_brute_force() {
+        for modulus in 1i64..20 {
+            for base in -10i64..10 {
+                let mut expected = 1 % modulus;
+                for exponent in 0u64..20 {
+                    assert_eq!(expected, mod_pow(base, exponent, modulus));
+                    expected = (expected * base).rem_euclid(modulus);
+                }
+            }
+        }
+        assert_eq!(1, mod_pow(i64::MAX - 1, i64::MAX as u64 - 1, i64::MAX));
+    }
+
+    /// The smallest non-negative solution of the congruences, found by trying every number up to
+    /// the least common multiple of the moduli.
+    fn crt_brute_force(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+        let combined = lcm_all(congruences.iter().map(|&(_, modulus)| modulus));
+        (0..combined)
+            .find(|t| congruences.iter().all(|&(r, m)| (t - r).rem_euclid(m) == 0))
+            .map(|t| (t, combined))
+    }
+
+    #[test]
+    fn test_crt_brute_force() {
+        for m1 in 1i64..13 {
+            for m2 in 1i64..13 {
+                for r1 in -2..m1 {
+                    for r2 in 0..m2 {
+                        let congruences = [(r1, m1), (r2, m2), (1, 3)];
+                        assert_eq!(crt_brute_force(&congruences),
+                                   crt(&congruences),
+                                   "congruences: {:?}",
+                                   congruences);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_crt_edge_cases() {
+        assert_eq!(Some((0, 1)), crt(&[]));
+        assert_eq!(Some((4, 7)), crt(&[(-3, 7)]));
+        assert_eq!(Some((5, 12)), crt(&[(1, 4), (5, 6), (5, 12)]));
+        assert_eq!(None, crt(&[(1, 2), (0, 4)]));
+
+        let primes = [1_000_003, 1_000_033, 1_000_037];
+        let target = 123_456_789_012_345_678 % (primes[0] * primes[1] * primes[2]);
+        let congruences = primes.iter().map(|&p| (target % p, p)).collect::<Vec<_>>();
+        assert_eq!(Some((target, primes[0] * primes[1] * primes[2])), crt(&congruences));
+    }
+
+    #[test]
+    fn test_timing_discs_example() {
+        // Disc #1 has 5 positions and starts at 4, disc #2 has 2 positions and starts at 1. The
+        // capsule reaches disc #i at time t + i, when it must be at position 0.
+        let discs = [(5, 4), (2, 1)];
+        let congruences = discs.iter()
+            .enumerate()
+            .map(|(index, &(positions, start))| (-(start + index as i64 + 1), positions))
+            .collect::<Vec<_>>();
+        assert_eq!(Some((5, 10)), crt(&congruences));
+    }
+}
@@ -0,0 +1,302 @@
+//! The MD5 message digest algorithm as described in RFC 1321.
+//!
+//! Puzzles usually hash a fixed prefix followed by a counter. The hasher can be cloned after the
+//! prefix has been added, and it implements `io::Write` so the counter can be written without
+//! allocating a string:
+//!
+//! ```
+//! use std::io::Write;
+//! use base::md5::{self, Md5};
+//!
+//! let mut salted = Md5::new();
+//! salted.update(b"abc");
+//!
+//! let mut hasher = salted.clone();
+//! write!(hasher, "{}", 3231929).unwrap();
+//! let digest = hasher.finish();
+//! assert_eq!(5, digest.leading_zeros());
+//! assert_eq!(1, digest.nibble(5));
+//! assert_eq!(digest, md5::digest("abc3231929"));
+//! assert_eq!("00000155f8105dff7f56ee10fa9b9abd", digest.to_string());
+//! ```
+
+use std::fmt;
+use std::io;
+
+const BLOCK_SIZE: usize = 64;
+
+const INITIAL_STATE: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+
+/// The amounts to rotate by in each round, four per round.
+const SHIFTS: [u32; 16] = [7, 12, 17, 22, 5, 9, 14, 20, 4, 11, 16, 23, 6, 10, 15, 21];
+
+/// `abs(sin(i + 1)) * 2^32` for each of the 64 steps.
+const SINES: [u32; 64] = [0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a,
+                          0xa8304613, 0xfd469501, 0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be,
+                          0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821, 0xf61e2562, 0xc040b340,
+                          0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+                          0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8,
+                          0x676f02d9, 0x8d2a4c8a, 0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c,
+                          0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70, 0x289b7ec6, 0xeaa127fa,
+                          0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+                          0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92,
+                          0xffeff47d, 0x85845dd1, 0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1,
+                          0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391];
+
+/// Computes the digest of `data` in one go.
+pub fn digest<T: AsRef<[u8]>>(data: T) -> Digest {
+    let mut hasher = Md5::new();
+    hasher.update(data.as_ref());
+    hasher.finish()
+}
+
+/// A streaming MD5 hasher, which can be fed data in any number of pieces.
+#[derive(Clone)]
+pub struct Md5 {
+    state: [u32; 4],
+    buffer: [u8; BLOCK_SIZE],
+    buffered: usize,
+    length: u64,
+}
+
+impl Md5 {
+    pub fn new() -> Md5 {
+        Md5 {
+            state: INITIAL_STATE,
+            buffer: [0; BLOCK_SIZE],
+            buffered: 0,
+            length: 0,
+        }
+    }
+
+    /// Adds `data` to the message.
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        if self.buffered > 0 {
+            let count = (BLOCK_SIZE - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + count].copy_from_slice(&data[..count]);
+            self.buffered += count;
+            data = &data[count..];
+            if self.buffered < BLOCK_SIZE {
+                return;
+            }
+            let block = self.buffer;
+            self.process(&block);
+            self.buffered = 0;
+        }
+
+        let mut blocks = data.chunks_exact(BLOCK_SIZE);
+        for block in &mut blocks {
+            self.process(block);
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    /// Pads the message and returns its digest.
+    pub fn finish(mut self) -> Digest {
+        let bit_length = self.length.wrapping_mul(8);
+        // A single 1 bit, then zeros until the length fits at the end of a block.
+        let padding_length = (BLOCK_SIZE + 55 - self.buffered) % BLOCK_SIZE + 1;
+        let mut padding = [0; BLOCK_SIZE];
+        padding[0] = 0x80;
+        self.update(&padding[..padding_length]);
+        self.update(&bit_length.to_le_bytes());
+        debug_assert_eq!(0, self.buffered);
+
+        let mut bytes = [0; 16];
+        for (chunk, word) in bytes.chunks_exact_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        Digest(bytes)
+    }
+
+    fn process(&mut self, block: &[u8]) {
+        let mut words = [0u32; 16];
+        for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+
+        let [mut a, mut b, mut c, mut d] = self.state;
+        for step in 0..64 {
+            let (f, index) = match step / 16 {
+                0 => ((b & c) | (!b & d), step),
+                1 => ((d & b) | (!d & c), (5 * step + 1) % 16),
+                2 => (b ^ c ^ d, (3 * step + 5) % 16),
+                _ => (c ^ (b | !d), (7 * step) % 16),
+            };
+            let shift = SHIFTS[(step / 16) * 4 + step % 4];
+            let rotated = a.wrapping_add(f)
+                .wrapping_add(SINES[step])
+                .wrapping_add(words[index])
+                .rotate_left(shift);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(rotated);
+        }
+
+        for (state, value) in self.state.iter_mut().zip(&[a, b, c, d]) {
+            *state = state.wrapping_add(*value);
+        }
+    }
+}
+
+impl Default for Md5 {
+    fn default() -> Md5 {
+        Md5::new()
+    }
+}
+
+impl fmt::Debug for Md5 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Md5").field("length", &self.length).finish()
+    }
+}
+
+/// Writing to a hasher adds the bytes to the message and never fails.
+impl io::Write for Md5 {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.update(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The 128-bit digest of a message. `Display` formats it as 32 lowercase hexadecimal digits.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Digest(pub [u8; 16]);
+
+impl Digest {
+    pub fn bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    /// The value of the hexadecimal digit at `index`, counting from the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is 32 or more.
+    pub fn nibble(&self, index: usize) -> u8 {
+        let byte = self.0[index / 2];
+        if index & 1 == 0 { byte >> 4 } else { byte & 0x0f }
+    }
+
+    /// Iterates over the values of all 32 hexadecimal digits.
+    pub fn nibbles(&self) -> impl Iterator<Item = u8> {
+        let digest = *self;
+        (0..32).map(move |index| digest.nibble(index))
+    }
+
+    /// The hexadecimal digit at `index` as a lowercase character.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is 32 or more.
+    pub fn hex_char(&self, index: usize) -> char {
+        ::std::char::from_digit(u32::from(self.nibble(index)), 16).unwrap()
+    }
+
+    /// The digest as lowercase hexadecimal ASCII digits.
+    pub fn to_hex(&self) -> [u8; 32] {
+        let mut hex = [0; 32];
+        for (index, digit) in hex.iter_mut().enumerate() {
+            *digit = self.hex_char(index) as u8;
+        }
+        hex
+    }
+
+    /// The number of leading hexadecimal digits that are zero.
+    pub fn leading_zeros(&self) -> usize {
+        self.nibbles().take_while(|&nibble| nibble == 0).count()
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// The test suite from appendix A.5 of RFC 1321.
+    const RFC_1321_VECTORS: [(&str, &str); 7] =
+        [("", "d41d8cd98f00b204e9800998ecf8427e"),
+         ("a", "0cc175b9c0f1b6a831c399e269772661"),
+         ("abc", "900150983cd24fb0d6963f7d28e17f72"),
+         ("message digest", "f96b697d7cb7938d525a2f31aaf161d0"),
+         ("abcdefghijklmnopqrstuvwxyz", "c3fcd3d76192e4007dfb496cca67e13b"),
+         ("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+          "d174ab98d277d9f5a5611c2c9f419d9f"),
+         ("12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+          "57edf4a22be3c955ac49da2e2107b67a")];
+
+    #[test]
+    fn test_rfc_1321_vectors() {
+        for &(message, expected) in &RFC_1321_VECTORS {
+            assert_eq!(expected, digest(message).to_string(), "digest of {:?}", message);
+        }
+    }
+
+    #[test]
+    fn test_streaming_matches_one_shot() {
+        for &(message, expected) in &RFC_1321_VECTORS {
+            for piece_size in 1..10 {
+                let mut hasher = Md5::new();
+                for piece in message.as_bytes().chunks(piece_size) {
+                    hasher.update(piece);
+                }
+                assert_eq!(expected, hasher.finish().to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn test_block_boundaries() {
+        // Messages around the block size need one or two blocks of padding.
+        let data = [b'x'; 130];
+        for length in 50..130 {
+            let mut hasher = Md5::new();
+            hasher.update(&data[..length / 2]);
+            hasher.update(&data[length / 2..length]);
+            assert_eq!(digest(&data[..length]), hasher.finish(), "length {}", length);
+        }
+        assert_eq!("04364420e25c512fd958a70738aa8f72", digest(&data[..55]).to_string());
+        assert_eq!("668a72d5ba17f08e62dabcafad6db14b", digest(&data[..56]).to_string());
+        assert_eq!("c1bb4f81d892b2d57947682aeb252456", digest(&data[..64]).to_string());
+    }
+
+    #[test]
+    fn test_write() {
+        let mut hasher = Md5::new();
+        let (first, second) = ("message", "digest");
+        write!(hasher, "{} {}", first, second).unwrap();
+        assert_eq!(digest("message digest"), hasher.finish());
+    }
+
+    #[test]
+    fn test_accessors() {
+        let digest = digest("abc");
+        assert_eq!(0x90, digest.bytes()[0]);
+        assert_eq!(9, digest.nibble(0));
+        assert_eq!(0, digest.nibble(1));
+        assert_eq!(0xf, digest.nibble(29));
+        assert_eq!('f', digest.hex_char(29));
+        assert_eq!(&b"900150983cd24fb0d6963f7d28e17f72"[..], &digest.to_hex()[..]);
+        assert_eq!(digest.to_string(),
+                   digest.nibbles().map(|n| format!("{:x}", n)).collect::<String>());
+        assert_eq!(0, digest.leading_zeros());
+    }
+}
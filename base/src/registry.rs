@@ -0,0 +1,196 @@
+//! This module contains a registry of all solutions, so that users of the solutions (e.g. the
+//! `aoc` utility) can find out which days and parts are available without hard-coding them.
+//!
+//! Every solution crate exports a [`Descriptor`](struct.Descriptor.html), which is then
+//! registered:
+//!
+//! ```
+//! use base::{Answer, Error, Part, ProblemSolver};
+//! use base::registry::{Descriptor, Registry};
+//!
+//! struct Solver;
+//!
+//! impl ProblemSolver for Solver {
+//!     fn solve(&self, _input: &str, _part: Part) -> Result<Answer, Error> {
+//!         Ok(Answer::from(42))
+//!     }
+//! }
+//!
+//! fn get_solver() -> Box<dyn ProblemSolver> {
+//!     Box::new(Solver)
+//! }
+//!
+//! const DESCRIPTOR: Descriptor = Descriptor {
+//!     day: 1,
+//!     title: "Example",
+//!     parts: &[Part::One],
+//!     constructor: get_solver,
+//! };
+//!
+//! let mut registry = Registry::new();
+//! registry.register(DESCRIPTOR);
+//! assert!(registry.solver(1, Part::One).is_ok());
+//! assert!(registry.solver(1, Part::Two).is_err());
+//! ```
+
+use std::collections::BTreeMap;
+
+use {Error, Part, ProblemSolver};
+
+/// Information about the solution for one day.
+#[derive(Debug, Clone, Copy)]
+pub struct Descriptor {
+    pub day: u32,
+    /// The title of the problem, as given on the Advent of Code website.
+    pub title: &'static str,
+    /// The parts of the problem that have been implemented.
+    pub parts: &'static [Part],
+    /// Creates a solver for the problem.
+    pub constructor: fn() -> Box<dyn ProblemSolver>,
+}
+
+impl Descriptor {
+    pub fn implements(&self, part: Part) -> bool {
+        self.parts.contains(&part)
+    }
+
+    pub fn solver(&self) -> Box<dyn ProblemSolver> {
+        (self.constructor)()
+    }
+}
+
+/// A collection of [`Descriptor`](struct.Descriptor.html)s, ordered by day.
+#[derive(Debug, Default)]
+pub struct Registry {
+    descriptors: BTreeMap<u32, Descriptor>,
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry { descriptors: BTreeMap::new() }
+    }
+
+    /// Adds a descriptor to the registry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a descriptor for the same day has already been registered, since that means
+    /// that two solution crates claim to solve the same problem.
+    pub fn register(&mut self, descriptor: Descriptor) {
+        if let Some(existing) = self.descriptors.insert(descriptor.day, descriptor) {
+            panic!("day {} registered twice: \"{}\" and \"{}\"",
+                   descriptor.day,
+                   existing.title,
+                   descriptor.title);
+        }
+    }
+
+    /// Returns the descriptor for `day`, or a `NotImplemented` error if there is none.
+    pub fn get(&self, day: u32) -> Result<&Descriptor, Error> {
+        self.descriptors
+            .get(&day)
+            .ok_or_else(|| Error::not_implemented(format!("day {} is not implemented", day)))
+    }
+
+    /// Creates a solver for the given day, but only if the given part has been implemented. If
+    /// not, a `NotImplemented` error is returned without running anything.
+    pub fn solver(&self, day: u32, part: Part) -> Result<Box<dyn ProblemSolver>, Error> {
+        let descriptor = self.get(day)?;
+        if descriptor.implements(part) {
+            Ok(descriptor.solver())
+        } else {
+            Err(Error::not_implemented(format!("day {} part {} is not implemented", day, part)))
+        }
+    }
+
+    /// Iterates over all registered descriptors in order of day.
+    pub fn descriptors(&self) -> impl Iterator<Item = &Descriptor> {
+        self.descriptors.values()
+    }
+
+    /// Returns a human-readable summary of all registered days, one day per line.
+    pub fn summary(&self) -> String {
+        let lines = self.descriptors().map(|descriptor| {
+            let parts = descriptor.parts
+                .iter()
+                .map(Part::to_string)
+                .collect::<Vec<String>>()
+                .join(", ");
+            format!("Day {:>2}: {} (parts: {})", descriptor.day, descriptor.title, parts)
+        });
+        lines.collect::<Vec<String>>().join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use Answer;
+
+    struct Solver;
+
+    impl ProblemSolver for Solver {
+        fn solve(&self, _input: &str, part: Part) -> Result<Answer, Error> {
+            match part {
+                Part::One => Ok(Answer::from(1)),
+                Part::Two => Ok(Answer::from(2)),
+            }
+        }
+    }
+
+    fn get_solver() -> Box<dyn ProblemSolver> {
+        Box::new(Solver)
+    }
+
+    fn descriptor(day: u32, parts: &'static [Part]) -> Descriptor {
+        Descriptor {
+            day,
+            title: "Test",
+            parts,
+            constructor: get_solver,
+        }
+    }
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new();
+        registry.register(descriptor(2, &[Part::One]));
+        registry.register(descriptor(1, &[Part::One, Part::Two]));
+        registry
+    }
+
+    #[test]
+    fn test_descriptors_ordered_by_day() {
+        let days = registry().descriptors().map(|d| d.day).collect::<Vec<u32>>();
+        assert_eq!(vec![1, 2], days);
+    }
+
+    #[test]
+    fn test_get_missing_day() {
+        assert!(matches!(registry().get(3), Err(Error::NotImplemented(_))));
+    }
+
+    #[test]
+    fn test_solver() {
+        let solver = registry().solver(1, Part::Two).unwrap();
+        assert_eq!(Answer::from(2), solver.solve("", Part::Two).unwrap());
+    }
+
+    #[test]
+    fn test_solver_missing_part() {
+        assert!(matches!(registry().solver(2, Part::Two), Err(Error::NotImplemented(_))));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_register_twice() {
+        let mut registry = registry();
+        registry.register(descriptor(1, &[]));
+    }
+
+    #[test]
+    fn test_summary() {
+        let expected = "Day  1: Test (parts: 1, 2)\nDay  2: Test (parts: 1)";
+        assert_eq!(expected, registry().summary());
+    }
+}
@@ -0,0 +1,287 @@
+//! Shortest path searches over any kind of state, e.g. a position in a maze or the whole state of
+//! a puzzle.
+//!
+//! The states that can be reached from a state are given by a successor function, so the graph
+//! never has to be built up front. For grids, `Position::neighbours4` or `Grid::neighbours` can be
+//! used directly:
+//!
+//! ```
+//! use base::coord::{Grid, Position};
+//! use base::search;
+//!
+//! let maze = "#####\n#..##\n##..#\n#####".parse::<Grid<char>>().unwrap();
+//! let goal = Position(3, 2);
+//! let open_neighbours = |&position: &Position| {
+//!     maze.neighbours(position).filter(|&(_, &c)| c == '.').map(|(n, _)| n)
+//! };
+//! let path = search::bfs(Position(1, 1), open_neighbours, |&position| position == goal).unwrap();
+//! assert_eq!(3, path.cost);
+//! assert_eq!(vec![Position(1, 1), Position(2, 1), Position(2, 2), Position(3, 2)], path.states);
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The types that can be used as the cost of a step in [`dijkstra`](fn.dijkstra.html) and
+/// [`astar`](fn.astar.html). It is implemented for all primitive integers. Costs must not be
+/// negative.
+pub trait Cost: Copy + Ord + Add<Output = Self> {
+    const ZERO: Self;
+}
+
+macro_rules! impl_cost {
+    ($($t:ty),*) => {
+        $(
+            impl Cost for $t {
+                const ZERO: $t = 0;
+            }
+        )*
+    }
+}
+
+impl_cost!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// A path that was found by a search.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Path<S, C> {
+    /// The states along the path, from the start up to and including the goal.
+    pub states: Vec<S>,
+    /// The total cost of the steps, which is the number of steps for a breadth-first search.
+    pub cost: C,
+    /// The number of states whose successors were generated before the goal was found.
+    pub expanded: usize,
+}
+
+impl<S, C> Path<S, C> {
+    /// The state that the path ends in, i.e. the goal.
+    pub fn goal(&self) -> &S {
+        self.states.last().expect("a path contains at least the start")
+    }
+
+    /// The number of steps in the path.
+    pub fn steps(&self) -> usize {
+        self.states.len() - 1
+    }
+}
+
+/// Finds a path with the fewest steps from `start` to a state for which `is_goal` returns `true`,
+/// or `None` if no such state can be reached.
+pub fn bfs<S, F, I, G>(start: S, mut successors: F, mut is_goal: G) -> Option<Path<S, usize>>
+    where S: Clone + Eq + Hash,
+          F: FnMut(&S) -> I,
+          I: IntoIterator<Item = S>,
+          G: FnMut(&S) -> bool
+{
+    let mut states = States::new(start.clone());
+    let mut seen = HashSet::new();
+    seen.insert(start);
+    let mut queue = VecDeque::new();
+    queue.push_back((0, 0));
+    let mut expanded = 0;
+
+    while let Some((steps, index)) = queue.pop_front() {
+        if is_goal(states.get(index)) {
+            return Some(states.path(index, steps, expanded));
+        }
+        expanded += 1;
+        let state = states.get(index).clone();
+        for next in successors(&state) {
+            if !seen.contains(&next) {
+                let next_index = states.push(next.clone(), index);
+                seen.insert(next);
+                queue.push_back((steps + 1, next_index));
+            }
+        }
+    }
+    None
+}
+
+/// Finds a cheapest path from `start` to a state for which `is_goal` returns `true`, or `None` if
+/// no such state can be reached. The successor function returns the next states together with the
+/// cost of the step to each of them.
+pub fn dijkstra<S, C, F, I, G>(start: S, successors: F, is_goal: G) -> Option<Path<S, C>>
+    where S: Clone + Eq + Hash,
+          C: Cost,
+          F: FnMut(&S) -> I,
+          I: IntoIterator<Item = (S, C)>,
+          G: FnMut(&S) -> bool
+{
+    astar(start, successors, |_| C::ZERO, is_goal)
+}
+
+/// Like [`dijkstra`](fn.dijkstra.html), but guided by a heuristic that estimates the remaining cost
+/// from a state to the nearest goal. The path is only guaranteed to be the cheapest if the
+/// heuristic never overestimates, e.g. the Manhattan distance to the goal in a grid.
+pub fn astar<S, C, F, I, H, G>(start: S,
+                               mut successors: F,
+                               mut heuristic: H,
+                               mut is_goal: G)
+                               -> Option<Path<S, C>>
+    where S: Clone + Eq + Hash,
+          C: Cost,
+          F: FnMut(&S) -> I,
+          I: IntoIterator<Item = (S, C)>,
+          H: FnMut(&S) -> C,
+          G: FnMut(&S) -> bool
+{
+    let mut queue = BinaryHeap::new();
+    queue.push(Reverse((heuristic(&start), C::ZERO, 0)));
+    let mut states = States::new(start.clone());
+    // The cheapest known cost of each state, and the index of the entry it was reached with.
+    let mut best = HashMap::new();
+    best.insert(start, (C::ZERO, 0));
+    let mut expanded = 0;
+
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        let state = states.get(index).clone();
+        if best[&state].1 != index {
+            // The state was reached more cheaply after this entry was queued.
+            continue;
+        }
+        if is_goal(&state) {
+            return Some(states.path(index, cost, expanded));
+        }
+        expanded += 1;
+        for (next, step_cost) in successors(&state) {
+            let next_cost = cost + step_cost;
+            if let Some(&(known_cost, _)) = best.get(&next) {
+                if known_cost <= next_cost {
+                    continue;
+                }
+            }
+            let estimate = next_cost + heuristic(&next);
+            let next_index = states.push(next.clone(), index);
+            best.insert(next, (next_cost, next_index));
+            queue.push(Reverse((estimate, next_cost, next_index)));
+        }
+    }
+    None
+}
+
+/// All states that have been reached during a search, with the index of the state they were
+/// reached from, so that paths can be rebuilt without requiring more than `Clone` of the states.
+struct States<S> {
+    entries: Vec<(S, usize)>,
+}
+
+impl<S: Clone> States<S> {
+    fn new(start: S) -> States<S> {
+        States { entries: vec![(start, 0)] }
+    }
+
+    fn get(&self, index: usize) -> &S {
+        &self.entries[index].0
+    }
+
+    fn push(&mut self, state: S, parent: usize) -> usize {
+        self.entries.push((state, parent));
+        self.entries.len() - 1
+    }
+
+    fn path<C>(&self, mut index: usize, cost: C, expanded: usize) -> Path<S, C> {
+        let mut states = vec![self.get(index).clone()];
+        while index != 0 {
+            index = self.entries[index].1;
+            states.push(self.get(index).clone());
+        }
+        states.reverse();
+        Path {
+            states,
+            cost,
+            expanded,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coord::{Grid, Position};
+
+    /// A small weighted graph where the path with the fewest steps is not the cheapest one.
+    fn graph(node: &char) -> Vec<(char, u32)> {
+        match *node {
+            'a' => vec![('b', 7), ('c', 1)],
+            'b' => vec![('d', 1)],
+            'c' => vec![('e', 2)],
+            'e' => vec![('b', 1), ('d', 5)],
+            _ => vec![],
+        }
+    }
+
+    fn maze() -> Grid<char> {
+        ["#########",
+         "#S..#...#",
+         "#.#.#.#.#",
+         "#.#...#G#",
+         "#########"]
+            .join("\n")
+            .parse()
+            .unwrap()
+    }
+
+    fn open_neighbours(maze: &Grid<char>, position: Position) -> Vec<Position> {
+        maze.neighbours(position).filter(|&(_, &c)| c != '#').map(|(n, _)| n).collect()
+    }
+
+    #[test]
+    fn test_bfs_maze() {
+        let maze = maze();
+        let start = maze.position(|&c| c == 'S').unwrap();
+        let path = bfs(start, |&p| open_neighbours(&maze, p), |&p| maze[p] == 'G').unwrap();
+        assert_eq!(12, path.cost);
+        assert_eq!(path.cost, path.steps());
+        assert_eq!(start, path.states[0]);
+        assert_eq!(Position(7, 3), *path.goal());
+        assert!(path.states.windows(2).all(|pair| pair[0].manhattan_distance(pair[1]) == 1));
+    }
+
+    #[test]
+    fn test_bfs_unreachable() {
+        let maze = maze();
+        let start = maze.position(|&c| c == 'S').unwrap();
+        assert_eq!(None, bfs(start, |&p| open_neighbours(&maze, p), |&p| p == Position(0, 0)));
+    }
+
+    #[test]
+    fn test_start_is_goal() {
+        let path = bfs(5, |&n| vec![n + 1], |&n| n == 5).unwrap();
+        assert_eq!(vec![5], path.states);
+        assert_eq!((0, 0), (path.cost, path.expanded));
+
+        let path = dijkstra('a', graph, |&n| n == 'a').unwrap();
+        assert_eq!((0, 0), (path.cost, path.steps()));
+    }
+
+    #[test]
+    fn test_bfs_fewest_steps() {
+        let path = bfs('a', |n| graph(n).into_iter().map(|(next, _)| next), |&n| n == 'd')
+            .unwrap();
+        assert_eq!(vec!['a', 'b', 'd'], path.states);
+    }
+
+    #[test]
+    fn test_dijkstra_cheapest() {
+        let path = dijkstra('a', graph, |&n| n == 'd').unwrap();
+        assert_eq!(vec!['a', 'c', 'e', 'b', 'd'], path.states);
+        assert_eq!(5, path.cost);
+        assert_eq!(None, dijkstra('d', graph, |&n| n == 'a'));
+    }
+
+    #[test]
+    fn test_astar_expands_fewer_states() {
+        let goal = Position(20, 0);
+        let successors = |p: &Position| p.neighbours4().map(|n| (n, 1u32)).collect::<Vec<_>>();
+        let with_heuristic = astar(Position(0, 0),
+                                   successors,
+                                   |p| p.manhattan_distance(goal),
+                                   |&p| p == goal)
+            .unwrap();
+        let without_heuristic = dijkstra(Position(0, 0), successors, |&p| p == goal).unwrap();
+        assert_eq!(20, with_heuristic.cost);
+        assert_eq!(20, without_heuristic.cost);
+        assert!(with_heuristic.expanded < without_heuristic.expanded);
+    }
+}
@@ -0,0 +1,165 @@
+//! A richer alternative to [`ProblemSolver`](../trait.ProblemSolver.html) that separates parsing
+//! the input from solving the parts of the problem.
+//!
+//! Implementing [`Solution`](trait.Solution.html) means that the input is only parsed once, even
+//! when both parts are solved, and that the time spent parsing can be measured on its own. Every
+//! `Solution` is also a `ProblemSolver`, so it can be registered like any other solver:
+//!
+//! ```
+//! use base::{Answer, Error, Part, ProblemSolver, Solution};
+//!
+//! struct Solver;
+//!
+//! impl Solution for Solver {
+//!     type Input = Vec<i64>;
+//!
+//!     fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+//!         input.split_whitespace().map(|n| n.parse().map_err(Error::from)).collect()
+//!     }
+//!
+//!     fn part_one(&self, numbers: &Self::Input) -> Result<Answer, Error> {
+//!         Ok(Answer::from(numbers.iter().sum::<i64>()))
+//!     }
+//! }
+//!
+//! let solver: Box<dyn ProblemSolver> = Box::new(Solver);
+//! assert_eq!(Answer::from(6), solver.solve("1 2 3", Part::One).unwrap());
+//!
+//! let solved = solver.solve_parts("1 2 3", &[Part::One, Part::Two]).unwrap();
+//! assert!(solved.parse_time.is_some());
+//! assert!(solved.parts[1].result.is_err());
+//! ```
+
+use std::time::{Duration, Instant};
+
+use {Answer, Error, Part, ProblemSolver};
+
+/// Implementations of this trait parse the input into an `Input` once, and then solve either part
+/// of the problem from the parsed input.
+pub trait Solution {
+    /// The parsed representation of the input.
+    type Input;
+
+    /// Parses the input string. Errors in the input should be reported as `Parse` errors.
+    fn parse(&self, input: &str) -> Result<Self::Input, Error>;
+
+    fn part_one(&self, input: &Self::Input) -> Result<Answer, Error>;
+
+    /// Solves part two. The default implementation returns a `NotImplemented` error, for problems
+    /// where only the first part has been solved.
+    fn part_two(&self, _input: &Self::Input) -> Result<Answer, Error> {
+        Err(Error::not_implemented("part two"))
+    }
+
+    /// Solves the given part from the parsed input.
+    fn solve_parsed(&self, input: &Self::Input, part: Part) -> Result<Answer, Error> {
+        match part {
+            Part::One => self.part_one(input),
+            Part::Two => self.part_two(input),
+        }
+    }
+}
+
+impl<S: Solution> ProblemSolver for S {
+    fn solve(&self, input: &str, part: Part) -> Result<Answer, Error> {
+        let input = self.parse(input)?;
+        self.solve_parsed(&input, part)
+    }
+
+    fn solve_parts(&self, input: &str, parts: &[Part]) -> Result<Solved, Error> {
+        let timer = Instant::now();
+        let input = self.parse(input)?;
+        let parse_time = timer.elapsed();
+
+        let parts = parts.iter()
+            .map(|&part| {
+                let timer = Instant::now();
+                let result = self.solve_parsed(&input, part);
+                SolvedPart {
+                    part,
+                    result,
+                    elapsed: timer.elapsed(),
+                }
+            })
+            .collect();
+        Ok(Solved {
+            parse_time: Some(parse_time),
+            parts,
+        })
+    }
+}
+
+/// The results of solving one or more parts of a problem from the same input.
+#[derive(Debug)]
+pub struct Solved {
+    /// How long parsing the input took, if the solver parses the input separately from solving.
+    pub parse_time: Option<Duration>,
+    /// The results of each part, in the order they were requested.
+    pub parts: Vec<SolvedPart>,
+}
+
+/// The result of solving one part of a problem.
+#[derive(Debug)]
+pub struct SolvedPart {
+    pub part: Part,
+    pub result: Result<Answer, Error>,
+    /// How long solving took. If the input was parsed separately, this excludes parsing.
+    pub elapsed: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::Cell;
+
+    /// Counts how many times the input has been parsed.
+    #[derive(Default)]
+    struct Counting {
+        parses: Cell<usize>,
+    }
+
+    impl Solution for Counting {
+        type Input = usize;
+
+        fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+            self.parses.set(self.parses.get() + 1);
+            if input.is_empty() {
+                Err(Error::parse("empty input"))
+            } else {
+                Ok(input.len())
+            }
+        }
+
+        fn part_one(&self, input: &Self::Input) -> Result<Answer, Error> {
+            Ok(Answer::from(*input as i64))
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        let solver = Counting::default();
+        assert_eq!(Answer::from(3), solver.solve("abc", Part::One).unwrap());
+        assert!(matches!(solver.solve("abc", Part::Two), Err(Error::NotImplemented(_))));
+        assert_eq!(2, solver.parses.get());
+    }
+
+    #[test]
+    fn test_solve_parts_parses_once() {
+        let solver = Counting::default();
+        let solved = solver.solve_parts("abcd", &[Part::One, Part::Two]).unwrap();
+        assert_eq!(1, solver.parses.get());
+        assert!(solved.parse_time.is_some());
+
+        let parts = solved.parts.iter().map(|p| p.part).collect::<Vec<Part>>();
+        assert_eq!(vec![Part::One, Part::Two], parts);
+        assert_eq!(Answer::from(4), *solved.parts[0].result.as_ref().unwrap());
+        assert!(matches!(solved.parts[1].result, Err(Error::NotImplemented(_))));
+    }
+
+    #[test]
+    fn test_solve_parts_parse_error() {
+        let solver = Counting::default();
+        assert!(matches!(solver.solve_parts("", &[Part::One]), Err(Error::Parse { .. })));
+    }
+}
@@ -1,22 +1,109 @@
 //! This module contains some useful utility functions that can be used by solutions in order to reduce the amount of boilerplate code related to e.g. reading input.
 
-use std::fs::File;
-use std::io::{BufReader, Read};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use Error;
 
 /// Takes a file path and returns a `Vec` of all the lines, without any trailing newline
-/// characters. Assumes that all lines in the file are readable as strings.
-pub fn lines_from_file(file_path: &str) -> Vec<String> {
-    read_file_as_string(file_path).split('\n').map(str::to_owned).collect()
+/// characters. Fails if the file cannot be read or is not valid UTF-8.
+pub fn lines_from_file<P: AsRef<Path>>(file_path: P) -> Result<Vec<String>, Error> {
+    Ok(read_file_as_string(file_path)?.lines().map(str::to_owned).collect())
+}
+
+/// Takes a file path and reads the contents of that file, which must be a text file. The read
+/// contents are returned as a new `String`. Fails if the file cannot be read or is not valid
+/// UTF-8, with an `Io` error that contains the path.
+pub fn read_file_as_string<P: AsRef<Path>>(file_path: P) -> Result<String, Error> {
+    let file_path = file_path.as_ref();
+    fs::read_to_string(file_path).map_err(|e| Error::io(file_path, e))
+}
+
+/// Opens a file for buffered reading, for solutions that want to process their input as it is
+/// read instead of reading it all into memory first.
+pub fn open<P: AsRef<Path>>(file_path: P) -> Result<BufReader<File>, Error> {
+    let file_path = file_path.as_ref();
+    File::open(file_path).map(BufReader::new).map_err(|e| Error::io(file_path, e))
+}
+
+/// Opens a file and returns an iterator over its lines, which reads the file as it goes. See
+/// [`Lines`](struct.Lines.html).
+pub fn read_lines<P: AsRef<Path>>(file_path: P) -> Result<Lines, Error> {
+    Ok(Lines {
+        lines: open(&file_path)?.lines(),
+        path: file_path.as_ref().to_path_buf(),
+    })
 }
 
-/// Takes a file path and reads the contents of that file, assuming that it is a text file. The
-/// read contents are returned as a new `String`.
-pub fn read_file_as_string(file_path: &str) -> String {
-    let file = File::open(file_path).unwrap();
-    let mut reader = BufReader::new(&file);
-    let mut buf = String::new();
-    let _ = reader.read_to_string(&mut buf);
-    buf
+/// Opens a file and returns an iterator over its contents in chunks of at most `chunk_size`
+/// bytes, which reads the file as it goes. See [`Chunks`](struct.Chunks.html).
+///
+/// # Panics
+///
+/// Panics if `chunk_size` is zero.
+pub fn read_chunks<P: AsRef<Path>>(file_path: P, chunk_size: usize) -> Result<Chunks, Error> {
+    assert!(chunk_size > 0, "chunk size must be positive");
+    Ok(Chunks {
+        reader: open(&file_path)?,
+        path: file_path.as_ref().to_path_buf(),
+        chunk_size,
+        done: false,
+    })
+}
+
+/// An iterator over the lines of a file, without any trailing newline characters. Each line is
+/// read when it is needed, so large files are never fully buffered. Errors while reading, e.g. a
+/// line that is not valid UTF-8, are returned as `Io` errors that contain the path.
+pub struct Lines {
+    lines: io::Lines<BufReader<File>>,
+    path: PathBuf,
+}
+
+impl Iterator for Lines {
+    type Item = Result<String, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let path = &self.path;
+        self.lines.next().map(|line| line.map_err(|e| Error::io(path, e)))
+    }
+}
+
+/// An iterator over the raw bytes of a file, in chunks of a fixed size. Only the last chunk can
+/// be shorter than the chunk size. Errors while reading are returned as `Io` errors that contain
+/// the path, after which the iterator is finished.
+pub struct Chunks {
+    reader: BufReader<File>,
+    path: PathBuf,
+    chunk_size: usize,
+    done: bool,
+}
+
+impl Iterator for Chunks {
+    type Item = Result<Vec<u8>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut chunk = Vec::with_capacity(self.chunk_size);
+        match self.reader.by_ref().take(self.chunk_size as u64).read_to_end(&mut chunk) {
+            Ok(0) => {
+                self.done = true;
+                None
+            }
+            Ok(_) => Some(Ok(chunk)),
+            Err(error) => {
+                self.done = true;
+                Some(Err(Error::io(&self.path, error)))
+            }
+        }
+    }
 }
 
 pub fn any_err<I, T, E>(mut iterator: I) -> Result<Vec<T>, E>
@@ -32,10 +119,299 @@ pub fn any_err<I, T, E>(mut iterator: I) -> Result<Vec<T>, E>
     Ok(ok_values)
 }
 
+/// Searches for the first indices for which a function returns a value, by checking chunks of
+/// consecutive indices on several threads at once. The matches are still returned in the order of
+/// their indices, as if the indices had been checked one by one.
+///
+/// ```
+/// use base::utils::ParallelSearch;
+///
+/// let matches = ParallelSearch::new(3)
+///     .chunk_size(10)
+///     .find_first(1, |i| if i % 7 == 0 { Some(i / 7) } else { None });
+/// assert_eq!(vec![(7, 1), (14, 2), (21, 3)], matches);
+/// ```
+pub struct ParallelSearch<'a> {
+    count: usize,
+    threads: usize,
+    chunk_size: u64,
+    cancel: Option<&'a AtomicBool>,
+    progress: Option<Box<dyn FnMut(Progress) + 'a>>,
+}
+
+/// How far a [`ParallelSearch`](struct.ParallelSearch.html) has come.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Progress {
+    /// The number of indices from the start that have all been checked.
+    pub checked: u64,
+    /// The number of matches among the checked indices.
+    pub found: usize,
+}
+
+impl<'a> ParallelSearch<'a> {
+    /// Creates a search for the first `count` matches, which uses one thread per available CPU.
+    pub fn new(count: usize) -> ParallelSearch<'a> {
+        ParallelSearch {
+            count,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            chunk_size: 1024,
+            cancel: None,
+            progress: None,
+        }
+    }
+
+    /// Sets the number of threads that check indices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is 0.
+    pub fn threads(mut self, threads: usize) -> ParallelSearch<'a> {
+        assert!(threads > 0, "a search needs at least one thread");
+        self.threads = threads;
+        self
+    }
+
+    /// Sets the number of consecutive indices that a thread checks at a time. Larger chunks have
+    /// less overhead, but more indices past the last match may be checked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    pub fn chunk_size(mut self, chunk_size: u64) -> ParallelSearch<'a> {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// Stops the search early when `cancel` is set, in which case the matches that were found
+    /// before the first unchecked index are returned.
+    pub fn cancel_flag(mut self, cancel: &'a AtomicBool) -> ParallelSearch<'a> {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Calls `progress` on the calling thread every time another chunk has been checked.
+    pub fn on_progress<F>(mut self, progress: F) -> ParallelSearch<'a>
+        where F: FnMut(Progress) + 'a
+    {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Checks the indices from `start` upwards with `check`, and returns the first matches with
+    /// their indices in increasing order. Fewer matches are only returned if the search was
+    /// cancelled or ran out of indices, which end at `u64::MAX`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `check` panics, after the other threads have stopped.
+    pub fn find_first<T, F>(mut self, start: u64, check: F) -> Vec<(u64, T)>
+        where T: Send,
+              F: Fn(u64) -> Option<T> + Sync
+    {
+        let mut matches = Vec::new();
+        if self.count == 0 {
+            return matches;
+        }
+
+        let chunk_size = self.chunk_size;
+        // The last chunk may be shorter, since it ends at u64::MAX.
+        let last_chunk = (u64::MAX - start) / chunk_size;
+        let next_chunk = AtomicU64::new(0);
+        let done = AtomicBool::new(false);
+        let cancel = self.cancel;
+        let cancelled = || cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed));
+        let stopped = || done.load(Ordering::Relaxed) || cancelled();
+
+        thread::scope(|scope| {
+            // If anything panics, the other threads have to stop before the panic can reach the
+            // caller, since the scope waits for all of them.
+            let _stop = StopOnDrop {
+                done: &done,
+                only_on_panic: false,
+            };
+            let (sender, receiver) = mpsc::channel();
+            for _ in 0..self.threads {
+                let sender = sender.clone();
+                let (check, next_chunk, stopped, done) = (&check, &next_chunk, &stopped, &done);
+                scope.spawn(move || {
+                    let _stop = StopOnDrop {
+                        done,
+                        only_on_panic: true,
+                    };
+                    loop {
+                        let chunk = next_chunk.fetch_add(1, Ordering::Relaxed);
+                        if chunk > last_chunk {
+                            return;
+                        }
+                        let first = start + chunk * chunk_size;
+                        let last = first.saturating_add(chunk_size - 1);
+                        let mut found = Vec::new();
+                        for index in first..=last {
+                            if stopped() {
+                                return;
+                            }
+                            if let Some(value) = check(index) {
+                                found.push((index, value));
+                            }
+                        }
+                        if sender.send((chunk, found)).is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+            drop(sender);
+
+            // Chunks can finish out of order, so they are held back until all earlier chunks
+            // are done as well.
+            let mut finished = BTreeMap::new();
+            let mut next_in_order = 0;
+            for (chunk, found) in receiver {
+                finished.insert(chunk, found);
+                while !cancelled() {
+                    let found = match finished.remove(&next_in_order) {
+                        Some(found) => found,
+                        None => break,
+                    };
+                    matches.extend(found);
+                    next_in_order += 1;
+                    if let Some(ref mut progress) = self.progress {
+                        progress(Progress {
+                            checked: next_in_order.saturating_mul(chunk_size)
+                                .min((u64::MAX - start).saturating_add(1)),
+                            found: matches.len().min(self.count),
+                        });
+                    }
+                }
+                if matches.len() >= self.count || cancelled() {
+                    done.store(true, Ordering::Relaxed);
+                    break;
+                }
+            }
+        });
+
+        matches.truncate(self.count);
+        matches
+    }
+}
+
+/// Stops the threads of a [`ParallelSearch`](struct.ParallelSearch.html) when it is dropped, or
+/// only when it is dropped because of a panic.
+struct StopOnDrop<'a> {
+    done: &'a AtomicBool,
+    only_on_panic: bool,
+}
+
+impl<'a> Drop for StopOnDrop<'a> {
+    fn drop(&mut self) {
+        if !self.only_on_panic || thread::panicking() {
+            self.done.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use std::env;
+    use std::error::Error as StdError;
+    use std::panic;
+    use std::process;
+
+    /// Writes `contents` to a file in the temporary directory that is unique to the test.
+    fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+        let path = env::temp_dir().join(format!("base-utils-{}-{}", process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn missing_file() -> PathBuf {
+        env::temp_dir().join(format!("base-utils-{}-does-not-exist", process::id()))
+    }
+
+    #[test]
+    fn test_read_file_as_string() {
+        let path = temp_file("read", b"R2, L3\n");
+        assert_eq!("R2, L3\n", read_file_as_string(&path).unwrap());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_read_file_as_string_missing() {
+        let path = missing_file();
+        let error = read_file_as_string(&path).unwrap_err();
+        assert!(error.source().is_some());
+        match error {
+            Error::Io { path: error_path, source } => {
+                assert_eq!(path, error_path);
+                assert_eq!(io::ErrorKind::NotFound, source.kind());
+            }
+            other => panic!("expected an I/O error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_read_file_as_string_not_utf8() {
+        let path = temp_file("not-utf8", &[b'a', 0xff, 0xfe, b'\n']);
+        let result = read_file_as_string(&path);
+        fs::remove_file(path).unwrap();
+        match result {
+            Err(Error::Io { source, .. }) => assert_eq!(io::ErrorKind::InvalidData, source.kind()),
+            other => panic!("expected an I/O error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_lines_from_file() {
+        let path = temp_file("lines", b"first\r\nsecond\n\nfourth\n");
+        let lines = lines_from_file(&path).unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(vec!["first", "second", "", "fourth"], lines);
+    }
+
+    #[test]
+    fn test_read_lines() {
+        let path = temp_file("read-lines", b"first\nsecond");
+        let lines = read_lines(&path).unwrap().collect::<Result<Vec<String>, Error>>().unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(vec!["first", "second"], lines);
+    }
+
+    #[test]
+    fn test_read_lines_not_utf8() {
+        let path = temp_file("read-lines-not-utf8", &[b'o', b'k', b'\n', 0xff, b'\n']);
+        let mut lines = read_lines(&path).unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!("ok", lines.next().unwrap().unwrap());
+        assert!(matches!(lines.next(), Some(Err(Error::Io { .. }))));
+    }
+
+    #[test]
+    fn test_read_lines_missing() {
+        assert!(matches!(read_lines(missing_file()), Err(Error::Io { .. })));
+    }
+
+    #[test]
+    fn test_read_chunks() {
+        let path = temp_file("chunks", b"abcdefg");
+        let chunks = read_chunks(&path, 3)
+            .unwrap()
+            .collect::<Result<Vec<Vec<u8>>, Error>>()
+            .unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(vec![b"abc".to_vec(), b"def".to_vec(), b"g".to_vec()], chunks);
+    }
+
+    #[test]
+    fn test_read_chunks_empty() {
+        let path = temp_file("chunks-empty", b"");
+        let mut chunks = read_chunks(&path, 3).unwrap();
+        fs::remove_file(path).unwrap();
+        assert!(chunks.next().is_none());
+    }
+
     #[test]
     fn test_any_err_only_ok() {
         let vals: Vec<Result<_, String>> = vec![Ok(1), Ok(2), Ok(3)];
@@ -65,4 +441,85 @@ mod tests {
         let err = any_err(vals.into_iter());
         assert!(err.is_err());
     }
+
+    #[test]
+    fn test_parallel_search_in_order() {
+        let is_square = |i: u64| {
+            let root = (i as f64).sqrt() as u64;
+            if root * root == i { Some(root) } else { None }
+        };
+        for &threads in &[1, 2, 7] {
+            let search = ParallelSearch::new(20).threads(threads).chunk_size(3);
+            let matches = search.find_first(5, is_square);
+            let expected = (3..23).map(|root| (root * root, root)).collect::<Vec<_>>();
+            assert_eq!(expected, matches, "threads: {}", threads);
+        }
+    }
+
+    #[test]
+    fn test_parallel_search_md5() {
+        use md5;
+
+        let matches = ParallelSearch::new(3).chunk_size(256).find_first(0, |i| {
+            let digest = md5::digest(format!("abc{}", i));
+            if digest.leading_zeros() >= 3 { Some(digest.hex_char(3)) } else { None }
+        });
+        let expected = (0..)
+            .filter_map(|i| {
+                let digest = md5::digest(format!("abc{}", i));
+                if digest.leading_zeros() >= 3 { Some((i, digest.hex_char(3))) } else { None }
+            })
+            .take(3)
+            .collect::<Vec<_>>();
+        assert_eq!(expected, matches);
+    }
+
+    #[test]
+    fn test_parallel_search_nothing_to_find() {
+        let matches = ParallelSearch::new(0).find_first(0, Some);
+        assert!(matches.is_empty());
+        let matches = ParallelSearch::new(5).chunk_size(10).find_first(u64::MAX - 25, Some);
+        assert_eq!(vec![(u64::MAX - 25, u64::MAX - 25)], matches[..1].to_vec());
+        assert_eq!(5, matches.len());
+    }
+
+    #[test]
+    fn test_parallel_search_last_partial_chunk() {
+        let start = u64::MAX - 9;
+        let matches = ParallelSearch::new(100).threads(3).chunk_size(4).find_first(start, Some);
+        assert_eq!((start..=u64::MAX).map(|i| (i, i)).collect::<Vec<_>>(), matches);
+    }
+
+    #[test]
+    fn test_parallel_search_panic() {
+        let result = panic::catch_unwind(|| {
+            ParallelSearch::new(1).threads(4).chunk_size(1).find_first(0, |i| -> Option<()> {
+                assert!(i != 100, "check failed");
+                None
+            })
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parallel_search_cancel() {
+        let cancel = AtomicBool::new(false);
+        let mut reports = Vec::new();
+        let matches = ParallelSearch::new(1000)
+            .threads(2)
+            .chunk_size(10)
+            .cancel_flag(&cancel)
+            .on_progress(|progress| {
+                reports.push(progress);
+                if progress.checked >= 100 {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            })
+            .find_first(0, |i| if i % 2 == 0 { Some(()) } else { None });
+        assert_eq!(50, matches.len());
+        assert!(matches.iter().enumerate().all(|(n, &(i, _))| i == 2 * n as u64));
+        assert_eq!(10, reports.len());
+        assert_eq!(Progress { checked: 10, found: 5 }, reports[0]);
+        assert_eq!(Progress { checked: 100, found: 50 }, reports[9]);
+    }
 }
@@ -1,64 +1,131 @@
 extern crate base;
-use base::{Part, ProblemSolver};
-use base::coord::{Direction, Position, Turn};
+use base::{Answer, Error, Part, ProblemSolver, Solution};
+use base::registry::Descriptor;
+use base::coord::{Direction, Position, Segment, Turn};
 
-use std::collections::HashSet;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
-pub fn get_solver() -> Box<ProblemSolver> {
+pub const DESCRIPTOR: Descriptor = Descriptor {
+    day: 1,
+    title: "No Time for a Taxicab",
+    parts: &[Part::One, Part::Two],
+    constructor: get_solver,
+};
+
+pub fn get_solver() -> Box<dyn ProblemSolver> {
     Box::new(Solver)
 }
 
 struct Solver;
 
-impl ProblemSolver for Solver {
-    fn solve(&self, input: &str, part: Part) -> Result<String, String> {
-        let instructions = parse_input(input)?;
-        match part {
-            Part::One => solve_part_one(&instructions),
-            Part::Two => solve_part_two(&instructions),
-        }
+impl Solution for Solver {
+    type Input = Vec<Instruction>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, instructions: &Self::Input) -> Result<Answer, Error> {
+        solve_part_one(instructions)
+    }
+
+    fn part_two(&self, instructions: &Self::Input) -> Result<Answer, Error> {
+        solve_part_two(instructions)
     }
 }
 
 // Here starts the actual solution, lol
 
-fn solve_part_one(instructions: &[Instruction]) -> Result<String, String> {
-    let mut path = travel(instructions);
-    match path.pop() {
-        Some(position) => Ok(position.taxi_distance().to_string()),
-        None => Err("something went wrong, the path was empty".to_owned()),
+fn solve_part_one(instructions: &[Instruction]) -> Result<Answer, Error> {
+    match travel(instructions).last() {
+        Some(segment) => Ok(Answer::from(segment.end().taxi_distance())),
+        None => Err(Error::internal("something went wrong, the path was empty")),
     }
 }
 
-fn solve_part_two(instructions: &[Instruction]) -> Result<String, String> {
-    let path = travel(instructions);
-    let mut visited = HashSet::new();
-    for position in &path {
-        if visited.contains(&position) {
-            return Ok(position.taxi_distance().to_string());
+fn solve_part_two(instructions: &[Instruction]) -> Result<Answer, Error> {
+    let mut visited = VisitedSegments::default();
+    for segment in travel(instructions) {
+        // The start of each segment is the end of the previous one, so it does not count as a
+        // crossing. The first segment starts at the origin, which has not been visited before.
+        let direction = match segment.direction() {
+            Some(direction) => direction,
+            None => continue,
+        };
+        let new_part = Segment::walk(segment.start().walk(direction),
+                                     direction,
+                                     segment.length() - 1);
+        let first_crossing = visited.candidates(&segment)
+            .filter_map(|visited| new_part.crossing(visited))
+            .min_by_key(|&crossing| new_part.start().manhattan_distance(crossing));
+        if let Some(position) = first_crossing {
+            return Ok(Answer::from(position.taxi_distance()));
         }
+        visited.insert(segment);
+    }
+    Err(Error::invalid_input("the path never crosses itself"))
+}
 
-        visited.insert(position);
+/// The segments that have been walked so far, indexed by the row of the horizontal ones and the
+/// column of the vertical ones, so that a new segment is only compared to the segments that are
+/// on its line or that are perpendicular to it and within its range.
+#[derive(Default)]
+struct VisitedSegments {
+    horizontal: BTreeMap<i32, Vec<Segment>>,
+    vertical: BTreeMap<i32, Vec<Segment>>,
+}
+
+impl VisitedSegments {
+    fn insert(&mut self, segment: Segment) {
+        if is_horizontal(&segment) {
+            self.horizontal.entry(segment.start().1).or_default().push(segment);
+        } else {
+            self.vertical.entry(segment.start().0).or_default().push(segment);
+        }
+    }
+
+    /// The visited segments that `segment` may cross.
+    fn candidates<'a>(&'a self, segment: &Segment) -> impl Iterator<Item = &'a Segment> + 'a {
+        let bounds = segment.bounds();
+        let (parallel, perpendicular, line, range) = if is_horizontal(segment) {
+            (&self.horizontal, &self.vertical, bounds.min().1, bounds.min().0..=bounds.max().0)
+        } else {
+            (&self.vertical, &self.horizontal, bounds.min().0, bounds.min().1..=bounds.max().1)
+        };
+        parallel.get(&line)
+            .into_iter()
+            .chain(perpendicular.range(range).map(|(_, segments)| segments))
+            .flatten()
     }
-    Err(format!("the path never crosses itself: {:?}", path))
 }
 
-fn parse_input(input: &str) -> Result<Vec<Instruction>, String> {
-    let instructions = input.trim().split(", ").map(Instruction::from_str);
-    base::utils::any_err(instructions)
+fn is_horizontal(segment: &Segment) -> bool {
+    segment.start().1 == segment.end().1
 }
 
-fn travel(instructions: &[Instruction]) -> Vec<Position> {
+fn parse_input(input: &str) -> Result<Vec<Instruction>, Error> {
+    let input = input.trim();
+    let mut column = 1;
+    let mut instructions = Vec::new();
+    for instruction_str in input.split(", ") {
+        let instruction = Instruction::from_str(instruction_str).map_err(|e| e.at(1, column))?;
+        instructions.push(instruction);
+        column += instruction_str.chars().count() + ", ".len();
+    }
+    Ok(instructions)
+}
+
+/// Follows the instructions from the origin, returning one segment per instruction.
+fn travel(instructions: &[Instruction]) -> Vec<Segment> {
     let mut direction = Direction::Up;
     let mut position = Position::new();
-    let mut path = vec![position];
+    let mut path = Vec::with_capacity(instructions.len());
     for instruction in instructions {
         direction.turn_mut(instruction.turn);
-        for _ in 0..instruction.distance {
-            position.walk_mut(direction);
-            path.push(position);
-        }
+        let segment = Segment::walk(position, direction, instruction.distance);
+        position = segment.end();
+        path.push(segment);
     }
     path
 }
@@ -70,22 +137,21 @@ struct Instruction {
 }
 
 impl FromStr for Instruction {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use std::error::Error;
+        if s.is_empty() || !s.is_char_boundary(1) {
+            return Err(Error::parse(format!("invalid instruction: {:?}", s)));
+        }
 
         let (turn_str, distance_str) = s.split_at(1);
         let turn = Turn::from_str(turn_str)?;
-        let distance = u32::from_str(distance_str).map_err(|e| e.description().to_string())?;
+        let distance = u32::from_str(distance_str)?;
 
         if distance == 0 {
-            Err("distance must be positive".to_string())
+            Err(Error::parse("distance must be positive"))
         } else {
-            Ok(Instruction {
-                turn: turn,
-                distance: distance,
-            })
+            Ok(Instruction { turn, distance })
         }
     }
 }
@@ -115,9 +181,65 @@ mod tests {
 
     #[test]
     fn instruction_from_str_err() {
-        let err_strs = ["L0", "R-1", "RL2"];
+        let err_strs = ["L0", "R-1", "RL2", "", "Ä1"];
         for err_str in &err_strs {
-            assert!(Instruction::from_str(err_str).is_err());
+            assert!(matches!(Instruction::from_str(err_str), Err(Error::Parse { .. })));
         }
     }
+
+    #[test]
+    fn parse_input_err_column() {
+        match parse_input("R2, L3, X4") {
+            Err(Error::Parse { line, column, .. }) => {
+                assert_eq!(Some(1), line);
+                assert_eq!(Some(9), column);
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    fn answer_part_two(input: &str) -> Answer {
+        solve_part_two(&parse_input(input).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn travel_long_distances() {
+        let path = travel(&parse_input("R1000000000, L1000000000").unwrap());
+        assert_eq!(2, path.len());
+        assert_eq!(Position(1000000000, 1000000000), path[1].end());
+    }
+
+    #[test]
+    fn part_two_long_distances() {
+        let input = "R100000000, L5, L50000000, L100000000";
+        assert_eq!(Answer::from(50000000u32), answer_part_two(input));
+    }
+
+    #[test]
+    fn part_two_first_crossing_along_segment() {
+        // The last segment crosses the first one at the origin, but it crosses the third one at
+        // Position(0, 3) before that.
+        let input = "R2, L3, L4, L6, L6, L8, L4, L10";
+        assert_eq!(Answer::from(3u32), answer_part_two(input));
+    }
+
+    #[test]
+    fn part_two_back_to_origin() {
+        assert_eq!(Answer::from(0u32), answer_part_two("R2, R2, R2, R2"));
+    }
+
+    #[test]
+    fn part_two_many_instructions_without_crossing() {
+        // A staircase that never crosses itself. Comparing each segment to all earlier ones would
+        // take billions of steps.
+        let input = ["R1", "L1"].repeat(50_000).join(", ");
+        let instructions = parse_input(&input).unwrap();
+        assert!(matches!(solve_part_two(&instructions), Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn part_two_no_crossing() {
+        let instructions = parse_input("R2, L3, R4").unwrap();
+        assert!(matches!(solve_part_two(&instructions), Err(Error::InvalidInput(_))));
+    }
 }
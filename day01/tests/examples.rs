@@ -1,25 +1,22 @@
 extern crate base;
-use base::Part;
+use base::{Answer, Part};
 
 extern crate day01;
 
-use std::str::FromStr;
-
-fn get_answer(input: &str, part: Part) -> u32 {
+fn get_answer(input: &str, part: Part) -> Answer {
     let solver = day01::get_solver();
-    let answer_str = &solver.solve(input, part).unwrap();
-    u32::from_str(answer_str).unwrap()
+    solver.solve(input, part).unwrap()
 }
 
 mod part1 {
     use super::*;
 
-    fn get_answer_p1(input: &str) -> u32 {
+    fn get_answer_p1(input: &str) -> Answer {
         get_answer(input, Part::One)
     }
 
     fn assert_correct_answer(input: &str, expected: u32) {
-        assert_eq!(get_answer_p1(input), expected);
+        assert_eq!(get_answer_p1(input), Answer::from(expected));
     }
 
     #[test]
@@ -41,12 +38,12 @@ mod part1 {
 mod part2 {
     use super::*;
 
-    fn get_answer_p2(input: &str) -> u32 {
+    fn get_answer_p2(input: &str) -> Answer {
         get_answer(input, Part::Two)
     }
 
     fn assert_correct_answer(input: &str, expected: u32) {
-        assert_eq!(get_answer_p2(input), expected);
+        assert_eq!(get_answer_p2(input), Answer::from(expected));
     }
 
     #[test]
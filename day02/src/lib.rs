@@ -1,29 +1,54 @@
 extern crate base;
 use base::FromChar;
-use base::{Part, ProblemSolver};
-use base::coord::{Direction, Position};
-
-pub fn get_solver() -> Box<ProblemSolver> {
+use base::{Answer, Error, Part, ProblemSolver, Solution};
+use base::registry::Descriptor;
+use base::coord::{Direction, Grid, Position, YDown};
+
+pub const DESCRIPTOR: Descriptor = Descriptor {
+    day: 2,
+    title: "Bathroom Security",
+    parts: &[Part::One, Part::Two],
+    constructor: get_solver,
+};
+
+pub fn get_solver() -> Box<dyn ProblemSolver> {
     Box::new(Solver)
 }
 
 struct Solver;
 
-impl ProblemSolver for Solver {
-    fn solve(&self, input: &str, part: Part) -> Result<String, String> {
-        let all_directions = parse_input(input)?;
-        match part {
-            Part::One => Ok(enter_code(Finger::new(StandardKeypad), all_directions)),
-            Part::Two => Ok(enter_code(Finger::new(StupidKeypad), all_directions)),
-        }
+impl Solution for Solver {
+    type Input = Vec<Vec<Direction>>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, all_directions: &Self::Input) -> Result<Answer, Error> {
+        let finger = Finger::new(keypad(STANDARD_KEYPAD)?)?;
+        Ok(Answer::from(enter_code(finger, all_directions)))
+    }
+
+    fn part_two(&self, all_directions: &Self::Input) -> Result<Answer, Error> {
+        let finger = Finger::new(keypad(STUPID_KEYPAD)?)?;
+        Ok(Answer::from(enter_code(finger, all_directions)))
     }
 }
 
-fn enter_code<K>(mut finger: Finger<K>, all_directions: Vec<Vec<Direction>>) -> String
-    where K: Keypad
-{
+/// The keypads as they look on the bathroom door, where spaces are places without a key.
+const STANDARD_KEYPAD: &str = "123\n456\n789";
+const STUPID_KEYPAD: &str = "  1  \n 234 \n56789\n ABC \n  D  ";
+
+/// The key that the finger starts on.
+const START_KEY: char = '5';
+
+fn keypad(layout: &str) -> Result<Grid<Key>, Error> {
+    layout.parse().map_err(|e| Error::internal("invalid keypad layout").with_source(e))
+}
+
+fn enter_code(mut finger: Finger, all_directions: &[Vec<Direction>]) -> String {
     let mut code = String::new();
-    for directions in &all_directions {
+    for directions in all_directions {
         for direction in directions {
             finger.walk(*direction);
         }
@@ -32,104 +57,67 @@ fn enter_code<K>(mut finger: Finger<K>, all_directions: Vec<Vec<Direction>>) ->
     code
 }
 
-fn parse_input(input: &str) -> Result<Vec<Vec<Direction>>, String> {
-    if input.len() == 0 {
-        return Err("parse_input: empty string".to_owned());
+fn parse_input(input: &str) -> Result<Vec<Vec<Direction>>, Error> {
+    if input.is_empty() {
+        return Err(Error::parse("parse_input: empty string"));
     }
     let str_lines = input.lines();
     let mut parsed = Vec::new();
-    for str_line in str_lines {
+    for (line_index, str_line) in str_lines.enumerate() {
         let directions = base::utils::any_err(str_line.chars()
-            .map(Direction::from_char))?;
+            .enumerate()
+            .map(|(column_index, c)| {
+                Direction::from_char(c).map_err(|e| e.at(line_index + 1, column_index + 1))
+            }))?;
         parsed.push(directions);
     }
     Ok(parsed)
 }
 
-struct Finger<K: Keypad> {
-    keypad: K,
-    current_position: Position,
-}
-
-impl<K: Keypad> Finger<K> {
-    fn new(keypad: K) -> Self {
-        Finger {
-            keypad: keypad,
-            current_position: K::initial_position(),
-        }
-    }
+/// A place on a keypad, which either has a key or is empty.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Key(Option<char>);
 
-    fn walk(&mut self, direction: Direction) {
-        let next_position = self.current_position.walk(direction);
-        self.current_position = match self.keypad.key(next_position) {
-            Some(_) => next_position,
-            None => self.current_position,
-        };
-    }
+impl FromChar for Key {
+    type Err = Error;
 
-    fn press(&self) -> String {
-        self.keypad.key(self.current_position).unwrap()
+    fn from_char(c: char) -> Result<Self, Self::Err> {
+        match c {
+            ' ' => Ok(Key(None)),
+            c if c.is_ascii_alphanumeric() => Ok(Key(Some(c))),
+            _ => Err(Error::parse(format!("not a valid key: {:?}", c))),
+        }
     }
 }
 
-trait Keypad {
-    fn key(&self, position: Position) -> Option<String>;
-
-    fn initial_position() -> Position;
+struct Finger {
+    keypad: Grid<Key>,
+    current_position: Position,
 }
 
-struct StandardKeypad;
-
-impl Keypad for StandardKeypad {
-    fn key(&self, position: Position) -> Option<String> {
-        match position {
-            Position(-1, 1) => Some(1.to_string()),
-            Position(0, 1) => Some(2.to_string()),
-            Position(1, 1) => Some(3.to_string()),
-            Position(-1, 0) => Some(4.to_string()),
-            Position(0, 0) => Some(5.to_string()),
-            Position(1, 0) => Some(6.to_string()),
-            Position(-1, -1) => Some(7.to_string()),
-            Position(0, -1) => Some(8.to_string()),
-            Position(1, -1) => Some(9.to_string()),
-            _ => None,
-        }
-    }
-
-    fn initial_position() -> Position {
-        Position(0, 0)
+impl Finger {
+    fn new(keypad: Grid<Key>) -> Result<Self, Error> {
+        let current_position = keypad.position(|&key| key == Key(Some(START_KEY)))
+            .ok_or_else(|| Error::internal(format!("the keypad has no {} key", START_KEY)))?;
+        Ok(Finger {
+            keypad,
+            current_position,
+        })
     }
-}
-
-struct StupidKeypad;
-
-impl Keypad for StupidKeypad {
-    fn key(&self, position: Position) -> Option<String> {
-        match position {
-            Position(0, 2) => Some(1.to_string()),
 
-            Position(-1, 1) => Some(2.to_string()),
-            Position(0, 1) => Some(3.to_string()),
-            Position(1, 1) => Some(4.to_string()),
-
-            Position(-2, 0) => Some(5.to_string()),
-            Position(-1, 0) => Some(6.to_string()),
-            Position(0, 0) => Some(7.to_string()),
-            Position(1, 0) => Some(8.to_string()),
-            Position(2, 0) => Some(9.to_string()),
-
-            Position(-1, -1) => Some("A".to_owned()),
-            Position(0, -1) => Some("B".to_owned()),
-            Position(1, -1) => Some("C".to_owned()),
-
-            Position(0, -2) => Some("D".to_owned()),
-
-            _ => None,
+    /// Moves the finger to the next key in `direction`, unless there is no key there.
+    fn walk(&mut self, direction: Direction) {
+        let next_position = self.current_position.walk_in::<YDown>(direction);
+        if let Some(&Key(Some(_))) = self.keypad.get(next_position) {
+            self.current_position = next_position;
         }
     }
 
-    fn initial_position() -> Position {
-        Position(-2, 0)
+    fn press(&self) -> String {
+        match self.keypad[self.current_position] {
+            Key(Some(key)) => key.to_string(),
+            Key(None) => unreachable!("the finger is never moved to an empty place"),
+        }
     }
 }
 
@@ -225,43 +213,80 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_input_err_position() {
+        match parse_input("ULD\nRXD\n") {
+            Err(Error::Parse { line, column, .. }) => {
+                assert_eq!(Some(2), line);
+                assert_eq!(Some(2), column);
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    fn finger(layout: &str) -> Finger {
+        Finger::new(keypad(layout).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_finger_starts_at_five() {
+        assert_eq!("5", finger(STANDARD_KEYPAD).press());
+        assert_eq!("5", finger(STUPID_KEYPAD).press());
+    }
+
     #[test]
     fn test_finger_walk() {
-        let mut finger = Finger::new(StandardKeypad);
+        let mut finger = finger(STANDARD_KEYPAD);
 
         finger.walk(Direction::Up);
-        assert_eq!(finger.current_position, Position(0, 1));
+        assert_eq!("2", finger.press());
 
         finger.walk(Direction::Left);
-        assert_eq!(finger.current_position, Position(-1, 1));
+        assert_eq!("1", finger.press());
 
         finger.walk(Direction::Down);
-        assert_eq!(finger.current_position, Position(-1, 0));
+        assert_eq!("4", finger.press());
 
         finger.walk(Direction::Right);
-        assert_eq!(finger.current_position, Position(0, 0));
+        assert_eq!("5", finger.press());
     }
 
     #[test]
     fn test_finger_walk_too_far() {
-        let mut finger = Finger::new(StandardKeypad);
+        let mut finger = finger(STANDARD_KEYPAD);
 
         finger.walk(Direction::Up);
         finger.walk(Direction::Up);
-        assert_eq!(finger.current_position, Position(0, 1));
+        assert_eq!("2", finger.press());
 
         finger.walk(Direction::Left);
         finger.walk(Direction::Left);
-        assert_eq!(finger.current_position, Position(-1, 1));
+        assert_eq!("1", finger.press());
 
         finger.walk(Direction::Down);
         finger.walk(Direction::Down);
         finger.walk(Direction::Down);
-        assert_eq!(finger.current_position, Position(-1, -1));
+        assert_eq!("7", finger.press());
 
         finger.walk(Direction::Right);
         finger.walk(Direction::Right);
         finger.walk(Direction::Right);
-        assert_eq!(finger.current_position, Position(1, -1));
+        assert_eq!("9", finger.press());
+    }
+
+    #[test]
+    fn test_finger_skips_empty_places() {
+        let mut finger = finger(STUPID_KEYPAD);
+
+        finger.walk(Direction::Up);
+        assert_eq!("5", finger.press());
+
+        finger.walk(Direction::Right);
+        finger.walk(Direction::Up);
+        finger.walk(Direction::Up);
+        assert_eq!("2", finger.press());
+
+        finger.walk(Direction::Left);
+        assert_eq!("2", finger.press());
     }
 }
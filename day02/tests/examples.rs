@@ -1,9 +1,9 @@
 extern crate base;
-use base::Part;
+use base::{Answer, Part};
 
 extern crate day02;
 
-fn get_answer(input: &str, part: Part) -> String {
+fn get_answer(input: &str, part: Part) -> Answer {
     let solver = day02::get_solver();
     solver.solve(input, part).unwrap()
 }
@@ -11,12 +11,12 @@ fn get_answer(input: &str, part: Part) -> String {
 mod part1 {
     use super::*;
 
-    fn get_answer_p1(input: &str) -> String {
+    fn get_answer_p1(input: &str) -> Answer {
         get_answer(input, Part::One)
     }
 
     fn assert_correct_answer(input: &str, expected: &str) {
-        assert_eq!(&get_answer_p1(input), expected);
+        assert_eq!(get_answer_p1(input), Answer::from(expected));
     }
 
     #[test]
@@ -29,12 +29,12 @@ mod part1 {
 mod part2 {
     use super::*;
 
-    fn get_answer_p2(input: &str) -> String {
+    fn get_answer_p2(input: &str) -> Answer {
         get_answer(input, Part::Two)
     }
 
     fn assert_correct_answer(input: &str, expected: &str) {
-        assert_eq!(&get_answer_p2(input), expected);
+        assert_eq!(get_answer_p2(input), Answer::from(expected));
     }
 
     #[test]
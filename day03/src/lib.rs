@@ -1,61 +1,91 @@
 extern crate base;
-use base::{Part, ProblemSolver};
+use base::{Answer, Error, Part, ProblemSolver, Solution};
+use base::registry::Descriptor;
 
-use std::error::Error;
 use std::str::FromStr;
 
-pub fn get_solver() -> Box<ProblemSolver> {
+pub const DESCRIPTOR: Descriptor = Descriptor {
+    day: 3,
+    title: "Squares With Three Sides",
+    parts: &[Part::One, Part::Two],
+    constructor: get_solver,
+};
+
+pub fn get_solver() -> Box<dyn ProblemSolver> {
     Box::new(Day03)
 }
 
 struct Day03;
 
-impl ProblemSolver for Day03 {
-    fn solve(&self, input: &str, part: Part) -> Result<String, String> {
-        let triangles = match part {
-            Part::One => parse_input_part1(input)?,
-            Part::Two => parse_input_part2(input)?,
-        };
-        Ok(count_triangles(&triangles).to_string())
-    }
-}
-
-fn parse_input_part1(input: &str) -> Result<Vec<(u32, u32, u32)>, String> {
-    let lines = input.lines();
-    base::utils::any_err(lines.map(parse_line))
-}
+impl Solution for Day03 {
+    type Input = Vec<Vec<u32>>;
 
-fn parse_line(line: &str) -> Result<(u32, u32, u32), String> {
-    let line = line.trim();
-    let strs = line.split_whitespace().collect::<Vec<&str>>();
-    let a_str = strs[0];
-    let b_str = strs[1];
-    let c_str = strs[2];
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_input(input)
+    }
 
-    let a = u32::from_str(a_str).unwrap();
-    let b = u32::from_str(b_str).unwrap();
-    let c = u32::from_str(c_str).unwrap();
+    fn part_one(&self, matrix: &Self::Input) -> Result<Answer, Error> {
+        let triangles = triangles_by_rows(matrix)?;
+        Ok(Answer::from(count_triangles(&triangles)))
+    }
 
-    Ok((a, b, c))
+    fn part_two(&self, matrix: &Self::Input) -> Result<Answer, Error> {
+        let triangles = triangles_by_columns(matrix)?;
+        Ok(Answer::from(count_triangles(&triangles)))
+    }
 }
 
-fn parse_input_part2(input: &str) -> Result<Vec<(u32, u32, u32)>, String> {
-    let lines = input.lines().collect::<Vec<&str>>();
-    let mut matrix = Vec::with_capacity(lines.len());
-    for line in &lines {
+/// Parses the input into a matrix of numbers, one row per line. The rows are read as triangles in
+/// part one and the columns in part two, so the shape is only checked when solving.
+fn parse_input(input: &str) -> Result<Vec<Vec<u32>>, Error> {
+    let mut matrix = Vec::new();
+    for (index, line) in input.lines().enumerate() {
         let elements = line.split_whitespace().collect::<Vec<&str>>();
         let mut row = Vec::with_capacity(elements.len());
         for element in &elements {
-            let number = u32::from_str(element).map_err(|e| e.description().to_owned())?;
+            let number = u32::from_str(element).map_err(|e| Error::from(e).at_line(index + 1))?;
             row.push(number);
         }
         matrix.push(row);
     }
+    Ok(matrix)
+}
+
+fn triangles_by_rows(matrix: &[Vec<u32>]) -> Result<Vec<(u32, u32, u32)>, Error> {
+    let mut triangles = Vec::with_capacity(matrix.len());
+    for (index, row) in matrix.iter().enumerate() {
+        if row.len() != 3 {
+            return Err(Error::parse(format!("expected three side lengths: {:?}", row))
+                .at_line(index + 1));
+        }
+        triangles.push((row[0], row[1], row[2]));
+    }
+    Ok(triangles)
+}
+
+fn triangles_by_columns(matrix: &[Vec<u32>]) -> Result<Vec<(u32, u32, u32)>, Error> {
+    if matrix.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let cols = matrix[0].len();
+    for (index, row) in matrix.iter().enumerate() {
+        if row.len() != cols {
+            return Err(Error::parse(format!("expected {} numbers like the first row: {:?}",
+                                            cols,
+                                            row))
+                .at_line(index + 1));
+        }
+    }
+    if !matrix.len().is_multiple_of(3) {
+        return Err(Error::parse(format!("expected the rows to come in groups of three, got {}",
+                                        matrix.len()))
+            .at_line(matrix.len()));
+    }
 
-    let transposed = transpose(matrix.clone());
+    let transposed = transpose(matrix.to_vec());
     let rows = transposed.len();
-    let cols = transposed[0].len();
-    let mut all_triangles = Vec::with_capacity((cols / 3) * rows);
+    let mut all_triangles = Vec::with_capacity((matrix.len() / 3) * rows);
     for row in &transposed {
         for chunk in row.chunks(3) {
             all_triangles.push((chunk[0], chunk[1], chunk[2]));
@@ -99,6 +129,14 @@ fn is_triangle(a: u32, b: u32, c: u32) -> bool {
 mod tests {
     use super::*;
 
+    fn parse_input_part1(input: &str) -> Result<Vec<(u32, u32, u32)>, Error> {
+        parse_input(input).and_then(|matrix| triangles_by_rows(&matrix))
+    }
+
+    fn parse_input_part2(input: &str) -> Result<Vec<(u32, u32, u32)>, Error> {
+        parse_input(input).and_then(|matrix| triangles_by_columns(&matrix))
+    }
+
     #[test]
     fn test_parse_input_part1_one_line() {
         let input = "5 10 15\n";
@@ -127,6 +165,15 @@ mod tests {
         assert_eq!(expected, parse_input_part1(input).unwrap());
     }
 
+    #[test]
+    fn test_parse_input_part1_err_line() {
+        let input = "5 10 15\n1 2\n";
+        match parse_input_part1(input) {
+            Err(Error::Parse { line, .. }) => assert_eq!(Some(2), line),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_parse_input_part2_one_triangle() {
         let input = "5\n10\n15\n";
@@ -159,6 +206,25 @@ mod tests {
         assert_eq!(expected, parse_input_part2(input2).unwrap());
     }
 
+    #[test]
+    fn test_parse_input_part2_err_row_count() {
+        let input = "3 4 5\n3 4 5";
+        match parse_input_part2(input) {
+            Err(Error::Parse { line, .. }) => assert_eq!(Some(2), line),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_input_part2_err_ragged_rows() {
+        for &(input, expected_line) in &[("3 4 5\n3 4\n3 4 5", 2), ("3 4\n3 4\n3 4 5", 3)] {
+            match parse_input_part2(input) {
+                Err(Error::Parse { line, .. }) => assert_eq!(Some(expected_line), line),
+                other => panic!("expected a parse error for {:?}, got {:?}", input, other),
+            }
+        }
+    }
+
     #[test]
     fn test_transpose_1x1() {
         let matrix = vec![vec![1]];
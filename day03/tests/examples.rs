@@ -1,14 +1,11 @@
 extern crate base;
-use base::Part;
+use base::{Answer, Part};
 
 extern crate day03;
 
-use std::str::FromStr;
-
-fn get_answer(input: &str, part: Part) -> u32 {
+fn get_answer(input: &str, part: Part) -> Answer {
     let solver = day03::get_solver();
-    let solution_str = &solver.solve(input, part).unwrap();
-    u32::from_str(solution_str).unwrap()
+    solver.solve(input, part).unwrap()
 }
 
 mod part1 {
@@ -17,7 +14,7 @@ mod part1 {
     #[test]
     fn example1() {
         let input = "5 10 25";
-        assert_eq!(get_answer(input, Part::One), 0);
+        assert_eq!(get_answer(input, Part::One), Answer::Integer(0));
     }
 }
 
@@ -28,6 +25,6 @@ mod part2 {
     fn example1() {
         let input = "101 301 501\n102 302 502\n103 303 503\n201 401 601\n202 402 602\n203 403 \
                      603\n";
-        assert_eq!(get_answer(input, Part::Two), 6);
+        assert_eq!(get_answer(input, Part::Two), Answer::Integer(6));
     }
 }
@@ -1,5 +1,6 @@
 extern crate base;
-use base::{Part, ProblemSolver};
+use base::{Answer, Error, Part, ProblemSolver, Solution};
+use base::registry::Descriptor;
 
 extern crate regex;
 use regex::Regex;
@@ -11,25 +12,34 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::str::FromStr;
 
-pub fn get_solver() -> Box<ProblemSolver> {
+pub const DESCRIPTOR: Descriptor = Descriptor {
+    day: 4,
+    title: "Security Through Obscurity",
+    parts: &[Part::One],
+    constructor: get_solver,
+};
+
+pub fn get_solver() -> Box<dyn ProblemSolver> {
     Box::new(Day04)
 }
 
 struct Day04;
 
-impl ProblemSolver for Day04 {
-    fn solve(&self, input: &str, part: Part) -> Result<String, String> {
-        let rooms = input.lines().map(Room::from_str);
-        let rooms = base::utils::any_err(rooms)?;
-        match part {
-            Part::One => {
-                Ok(rooms.iter()
-                    .filter(|room| room.is_real())
-                    .fold(0, |acc, ref room| acc + room.sector_id)
-                    .to_string())
-            }
-            Part::Two => Err("Day04::solve: not implemented yet".to_owned()),
-        }
+impl Solution for Day04 {
+    type Input = Vec<Room>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        let rooms = input.lines()
+            .enumerate()
+            .map(|(index, line)| Room::from_str(line).map_err(|e| e.at_line(index + 1)));
+        base::utils::any_err(rooms)
+    }
+
+    fn part_one(&self, rooms: &Self::Input) -> Result<Answer, Error> {
+        let sum = rooms.iter()
+            .filter(|room| room.is_real())
+            .fold(0, |acc, room| acc + room.sector_id);
+        Ok(Answer::from(sum))
     }
 }
 
@@ -41,21 +51,22 @@ struct Room {
 }
 
 impl FromStr for Room {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         lazy_static! {
             static ref RE: Regex = Regex::new(r"^(?P<name>[a-z]+(-[a-z]+)*?)-(?P<sid_chk>\d+\[[a-z]{5}\])$").unwrap();
         }
 
-        let captures = RE.captures(s).ok_or(format!("Room::from_str: invalid room string: {}", s))?;
+        let captures = RE.captures(s)
+            .ok_or_else(|| Error::parse(format!("Room::from_str: invalid room string: {}", s)))?;
 
         let name = remove_dashes(captures.name("name").unwrap());
         let (sector_id, checksum) = sector_id_and_checksum(captures.name("sid_chk").unwrap())?;
         Ok(Room {
-            name: name,
-            sector_id: sector_id,
-            checksum: checksum,
+            name,
+            sector_id,
+            checksum,
         })
     }
 }
@@ -86,15 +97,16 @@ fn remove_dashes(s: &str) -> String {
     s.split('-').collect::<String>()
 }
 
-fn sector_id_and_checksum(s: &str) -> Result<(u32, String), String> {
+fn sector_id_and_checksum(s: &str) -> Result<(u32, String), Error> {
     lazy_static! {
         static ref RE: Regex = Regex::new(r"^(?P<sector_id>\d+)\[(?P<checksum>[a-z]{5})\]$").unwrap();
     }
 
-    let captures = RE.captures(s).ok_or(format!("sector_id_and_checksum: invalid string: {}", s))?;
+    let captures = RE.captures(s)
+        .ok_or_else(|| Error::parse(format!("sector_id_and_checksum: invalid string: {}", s)))?;
 
     let sector_id_str = captures.name("sector_id").unwrap();
-    let sector_id = u32::from_str(sector_id_str).unwrap();
+    let sector_id = u32::from_str(sector_id_str)?;
 
     let checksum_str = captures.name("checksum").unwrap();
 
@@ -152,6 +164,18 @@ mod tests {
             assert_eq!(200, room.sector_id);
             assert_eq!("decoy".to_owned(), room.checksum);
         }
+
+        #[test]
+        fn invalid() {
+            let room_str = "totally-real-room[decoy]";
+            assert!(matches!(Room::from_str(room_str), Err(Error::Parse { .. })));
+        }
+
+        #[test]
+        fn sector_id_too_large() {
+            let room_str = "totally-real-room-99999999999[decoy]";
+            assert!(matches!(Room::from_str(room_str), Err(Error::Parse { .. })));
+        }
     }
 
     mod test_room {